@@ -1,9 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use crate::api_handling::api_desc_dir::{
     OutputFiles, ParameterAndType, RequestFile, RequestFunction, ResponseFile, SpecVersion,
 };
-use crate::api_handling::{Field, TemplateAction};
-use crate::api_handling::helper::rustify_string;
+use crate::api_handling::{Field, TemplateAction, TemplateDefault, TemplateEnum, TemplateRange, TemplateVariant};
+use crate::api_handling::helper::{camel_case, rustify_string_with};
+use crate::config::{
+    AllowedValuesMapping, BinaryMapping, Config, DateTimeMapping, IntegerMapping, RangeMapping, TypeOptions, UuidMapping,
+};
+use crate::error::GeneratorError;
 
 ///Struct to deserialize response from "fritz.box/xyzSCPD.xml" into.
 #[derive(Deserialize, Debug, Default)]
@@ -65,6 +69,9 @@ pub struct StateVariable {
     #[serde(rename = "allowedValueList")]
     #[serde(default)]
     pub allowed_value_list: AllowedValueList,
+    #[serde(rename = "allowedValueRange")]
+    #[serde(default)]
+    pub allowed_value_range: Option<AllowedValueRange>,
 }
 
 ///Struct to deserialize the AllowedValueList part of the response from "fritz.box/xyzSCPD.xml" into.
@@ -74,29 +81,47 @@ pub struct AllowedValueList {
     pub allowed_value: Vec<String>,
 }
 
+///Struct to deserialize the AllowedValueRange part of the response from "fritz.box/xyzSCPD.xml" into.
+#[derive(Deserialize, Debug, Default)]
+pub struct AllowedValueRange {
+    #[serde(default)]
+    pub minimum: String,
+    #[serde(default)]
+    pub maximum: String,
+    #[serde(default)]
+    pub step: String,
+}
+
 impl ApiDesc {
     ///Takes  an `OutputFiles`, `name`, `control_url` and `service_type` and populates the `OutputFiles`
+    ///The `OutputFiles` stay untouched if an error occurs.
     pub fn fill_output_files(
         &self,
         output_files: &mut OutputFiles,
         name: &str,
         control_url: &str,
         service_type: &str,
-    ) {
-        let rusty_name = rustify_string(name);
+    ) -> Result<(), GeneratorError> {
+        let config = output_files.config;
+        let naming = &config.naming;
+        let rusty_name = rustify_string_with(name, naming);
         let mut response_file = ResponseFile::default();
         let mut request_file = RequestFile::default();
         response_file.name = rusty_name.clone();
         request_file.name = rusty_name;
 
         let mut actions: Vec<TemplateAction> = Vec::new();
+        let mut annotation_string: Vec<String> = Vec::new();
+        let mut glue: Vec<&'static str> = Vec::new();
+        let mut definitions: BTreeMap<String, TypeDefinition> = BTreeMap::new();
 
         for action in &self.action_list.action {
 
             let mut request_function = RequestFunction::new(
                 action.name.clone(),
                 service_type.to_string(),
-                control_url.to_string()
+                control_url.to_string(),
+                naming,
             );
 
             let mut template_action = TemplateAction {
@@ -104,10 +129,14 @@ impl ApiDesc {
                     .replace(['-', '_'], "")),
                 ..Default::default()
             };
+            request_function.struct_name = format!("{}Request", action.name.replace(['-', '_'], ""));
+            request_function.access_right = config.access_right(service_type, &action.name).map(|right| right.variant());
+            request_function.response_type = format!(
+                "super::super::{}::{}{}::{}",
+                output_files.response_output_folder, output_files.prefix, response_file.name, template_action.name
+            );
 
-            output_files
-                .annotation_string
-                .push(action.name.clone());
+            annotation_string.push(action.name.clone());
 
             let mut fields: Vec<Field> = Vec::new();
 
@@ -116,18 +145,72 @@ impl ApiDesc {
                 let mut field = Field::default();
 
                 if argument.direction == "out" {
+                    let rust_type = self.search_state_variable_type(service_type, action, argument, config)?;
                     field.xml_name = argument.name.clone();
-                    field.name = rustify_string(&argument.name);
-                    field.r#type = self
-                        .search_state_variable_type(argument.related_state_variable.as_str());
+                    field.name = rustify_string_with(&argument.name, naming);
+                    if let Some(definition) = rust_type.definition {
+                        definitions.insert(rust_type.name.clone(), definition);
+                    }
+                    field.r#type = rust_type.name;
+                    match rust_type.deserialize_with {
+                        Some(DeserializeWith::MultiUse(function)) => {
+                            field.deserialize_with = format!("super::{}multi_use::{}", output_files.prefix, function);
+                            glue.push(function);
+                        }
+                        Some(DeserializeWith::Path(path)) => field.deserialize_with = path,
+                        None => {}
+                    }
 
                     fields.push(field);
 
                 } else if argument.direction == "in" {
+                    let rust_type = self.search_state_variable_type(service_type, action, argument, config)?;
+                    let parameter_name_rusty = rustify_string_with(&argument.name, naming);
+                    // the definitions and defaults are generated into the response file of the service
+                    let response_module = format!(
+                        "super::super::{}::{}{}",
+                        output_files.response_output_folder, output_files.prefix, response_file.name
+                    );
+                    let type_name = match rust_type.definition {
+                        Some(definition) => {
+                            let type_name = format!("{}::{}", response_module, rust_type.name);
+                            definitions.insert(rust_type.name, definition);
+                            type_name
+                        }
+                        None => rust_type.name,
+                    };
+                    let mut format_argument = match rust_type.format {
+                        Some(format) => format.replace("{}", &parameter_name_rusty),
+                        None => parameter_name_rusty.clone(),
+                    };
+                    let default_constant = self
+                        .related_default(argument)
+                        .filter(|_| config.requests.optional_defaults)
+                        .map(|variable| default_constant_name(&variable.name, naming));
+                    if let Some(default_constant) = &default_constant {
+                        format_argument = format!(
+                            "{name}.as_ref().map_or_else(|| {module}::{default}.to_string(), |{name}| {value}.to_string())",
+                            name = parameter_name_rusty,
+                            module = response_module,
+                            default = default_constant,
+                            value = format_argument,
+                        );
+                    }
+                    // the request functions take strings and bytes borrowed, the request structs own them
+                    let owned_argument = match (type_name.as_str(), default_constant.is_some()) {
+                        ("String", false) => format!("{}.to_string()", parameter_name_rusty),
+                        ("String", true) => format!("{}.map(str::to_string)", parameter_name_rusty),
+                        ("Vec<u8>", false) => format!("{}.to_vec()", parameter_name_rusty),
+                        ("Vec<u8>", true) => format!("{}.map(<[u8]>::to_vec)", parameter_name_rusty),
+                        _ => String::new(),
+                    };
                     let param = ParameterAndType {
                         parameter_name: argument.name.clone(),
-                        parameter_name_rusty: rustify_string(&argument.name),
-                        type_name: self.search_state_variable_type(argument.related_state_variable.as_str())
+                        format_argument,
+                        owned_argument,
+                        parameter_name_rusty,
+                        type_name,
+                        optional: default_constant.is_some(),
                     };
 
                     request_function.parameter.push(param);
@@ -140,38 +223,322 @@ impl ApiDesc {
             request_file.request_functions.push(request_function);
         }
 
+        let mut enums: Vec<TemplateEnum> = Vec::new();
+        let mut ranges: Vec<TemplateRange> = Vec::new();
+        for definition in definitions.into_values() {
+            match definition {
+                TypeDefinition::Enum(enumeration) => enums.push(enumeration),
+                TypeDefinition::Range(range) => ranges.push(range),
+            }
+        }
+        if !enums.is_empty() {
+            glue.push("parse_value_error");
+        }
+        if !ranges.is_empty() {
+            glue.push("range_error");
+        }
+
         // generate the response file content via handlebars
-        let mut template_data:HashMap<&str, Vec<TemplateAction>> = HashMap::new();
-        template_data.insert("actions", actions);
-        let template_actions_content = output_files.handlebars.render("action_response_types", &template_data).unwrap();
+        let mut template_data: HashMap<&str, serde_json::Value> = HashMap::new();
+        template_data.insert("actions", serde_json::json!(actions));
+        template_data.insert("enums", serde_json::json!(enums));
+        template_data.insert("ranges", serde_json::json!(ranges));
+        template_data.insert("defaults", serde_json::json!(self.defaults(naming)));
+        template_data.insert("multi_use", serde_json::json!(format!("super::{}multi_use", output_files.prefix)));
+        let template_actions_content = output_files.handlebars.render("action_response_types", &template_data)
+            .map_err(|source| GeneratorError::TemplateRender {
+                template: "action_response_types".to_string(),
+                source,
+            })?;
         response_file.content = template_actions_content;
         output_files.response_files.push(response_file);
 
         output_files.request_files.push(request_file);
+        output_files.annotation_string.extend(annotation_string);
+        output_files.glue.extend(glue);
+
+        Ok(())
     }
 
-    /// Searches for the requested variable and returns the corresponding type.
-    /// If you encounter a panic here, please open a ticket with the output of `_ => print!("{}", variable.data_type.as_str()),`
-    fn search_state_variable_type(&self, state_variable_name: &str) -> String {
+    /// Returns the constants of all state variables with a default value, named with `naming`.
+    fn defaults(&self, naming: &BTreeMap<String, String>) -> Vec<TemplateDefault> {
+        let mut defaults: Vec<TemplateDefault> = Vec::new();
         for variable in &self.service_state_table.state_variable {
-            if state_variable_name.eq(&variable.name) {
-                match variable.data_type.as_str() {
-                    "boolean" => return String::from("bool"),
-                    "ui1" => return String::from("u32"),
-                    "ui2" => return String::from("u32"),
-                    "ui4" => return String::from("u32"),
-                    "i1" => return String::from("i32"),
-                    "i2" => return String::from("i32"),
-                    "i4" => return String::from("i32"),
-                    "string" => return String::from("String"),
-                    "uuid" => return String::from("String"),
-                    "dateTime" => return String::from("String"),
-                    _ => print!("{}", variable.data_type.as_str()),
-                };
+            let name = default_constant_name(&variable.name, naming);
+            if variable.default_value.is_empty() || defaults.iter().any(|default| default.name == name) {
+                continue;
             }
+            defaults.push(TemplateDefault {
+                name,
+                variable: variable.name.clone(),
+                literal: format!("{:?}", variable.default_value),
+            });
         }
+        defaults
+    }
 
-        panic!("variable Type not implemented, please open a ticket")
+    /// Returns the related state variable of `argument` if it has a default value.
+    fn related_default(&self, argument: &Argument) -> Option<&StateVariable> {
+        self.service_state_table
+            .state_variable
+            .iter()
+            .find(|variable| variable.name == argument.related_state_variable)
+            .filter(|variable| !variable.default_value.is_empty())
     }
 
+    /// Searches for the related state variable of `argument` and returns the corresponding type.
+    /// `Config::type_overrides` take precedence over `Config::type_mapping`, which takes precedence over the built-in types.
+    fn search_state_variable_type(
+        &self,
+        service_type: &str,
+        action: &Action,
+        argument: &Argument,
+        config: &Config,
+    ) -> Result<RustType, GeneratorError> {
+        let variable = self
+            .service_state_table
+            .state_variable
+            .iter()
+            .find(|variable| variable.name == argument.related_state_variable)
+            .ok_or_else(|| GeneratorError::MissingStateVariable {
+                service: service_type.to_string(),
+                action: action.name.clone(),
+                argument: argument.name.clone(),
+                variable: argument.related_state_variable.clone(),
+            })?;
+
+        let type_override = config
+            .type_overrides
+            .iter()
+            .filter(|type_override| type_override.matches(service_type, &action.name, &argument.name, &variable.name))
+            .min_by_key(|type_override| type_override.argument.is_none());
+        if let Some(type_override) = type_override {
+            return Ok(RustType {
+                name: type_override.type_name.clone(),
+                deserialize_with: type_override.deserialize_with.clone().map(DeserializeWith::Path),
+                format: type_override.format_with.as_ref().map(|format_with| format!("{}({{}})", format_with)),
+                definition: None,
+            });
+        }
+        if config.types.allowed_values == AllowedValuesMapping::Enum
+            && variable.data_type == "string"
+            && !variable.allowed_value_list.allowed_value.is_empty()
+        {
+            let enumeration = allowed_values_enum(variable, config.types.unknown_variant);
+            return Ok(RustType {
+                name: enumeration.name.clone(),
+                deserialize_with: None,
+                format: None,
+                definition: Some(TypeDefinition::Enum(enumeration)),
+            });
+        }
+
+        let rust_type = match config.type_mapping.get(&variable.data_type) {
+            Some(type_name) => RustType::plain(type_name),
+            None => rust_type(&variable.data_type, &config.types).ok_or_else(|| GeneratorError::UnknownDataType {
+                service: service_type.to_string(),
+                action: action.name.clone(),
+                variable: variable.name.clone(),
+                data_type: variable.data_type.clone(),
+            })?,
+        };
+        let range = match (&variable.allowed_value_range, config.types.ranges) {
+            (Some(range), RangeMapping::Checked) => allowed_range(variable, range, &rust_type.name),
+            _ => None,
+        };
+
+        Ok(match range {
+            Some(range) => RustType {
+                name: range.name.clone(),
+                deserialize_with: None,
+                format: None,
+                definition: Some(TypeDefinition::Range(range)),
+            },
+            None => rust_type,
+        })
+    }
+
+}
+
+/// Rust type of a state variable and the glue needed if the type doesn't match the XML representation.
+#[derive(Debug, Clone)]
+pub struct RustType {
+    pub name: String,
+    pub deserialize_with: Option<DeserializeWith>,
+    /// Expression formatting the request parameter, `{}` is replaced with the parameter name.
+    pub format: Option<String>,
+    /// Type to generate into the response file, if the type is generated from allowed values or a range.
+    pub definition: Option<TypeDefinition>,
+}
+
+/// Type generated from the allowed values or the range of a state variable.
+#[derive(Debug, Clone)]
+pub enum TypeDefinition {
+    Enum(TemplateEnum),
+    Range(TemplateRange),
+}
+
+/// Function deserializing a response field.
+#[derive(Debug, Clone)]
+pub enum DeserializeWith {
+    /// Function generated into the multi use file, e.g. `deserialize_base64`.
+    MultiUse(&'static str),
+    /// Path of a user provided function, see `TypeOverride`.
+    Path(String),
+}
+
+impl RustType {
+    /// A type that is deserialized and formatted as is.
+    fn plain(name: &str) -> Self {
+        RustType {
+            name: name.to_string(),
+            deserialize_with: None,
+            format: None,
+            definition: None,
+        }
+    }
+}
+
+/// Returns the name of the constant holding the default value of the state variable `variable`, e.g. `DEFAULT_ENABLE`.
+/// A name configured in `naming` replaces the snake case of `variable`.
+fn default_constant_name(variable: &str, naming: &BTreeMap<String, String>) -> String {
+    format!("DEFAULT_{}", rustify_string_with(variable, naming).to_uppercase())
+}
+
+/// Returns the newtype checking `range` for `variable` of the integer type `type_name`.
+/// Missing or unparsable bounds are replaced by the bounds of `type_name`, a missing step by 1.
+/// Returns `None` for non integer types and empty ranges.
+fn allowed_range(variable: &StateVariable, range: &AllowedValueRange, type_name: &str) -> Option<TemplateRange> {
+    let (type_minimum, type_maximum) = integer_bounds(type_name)?;
+    let bound = |value: &str, default: i128| value.trim().parse::<i128>().unwrap_or(default).clamp(type_minimum, type_maximum);
+    let minimum = bound(&range.minimum, type_minimum);
+    let maximum = bound(&range.maximum, type_maximum);
+    let step = bound(&range.step, 1).max(1);
+    if minimum > maximum {
+        return None;
+    }
+
+    Some(TemplateRange {
+        name: camel_case(&variable.name),
+        variable: variable.name.clone(),
+        r#type: type_name.to_string(),
+        minimum: minimum.to_string(),
+        maximum: maximum.to_string(),
+        step: step.to_string(),
+        check_step: step > 1,
+    })
+}
+
+/// Returns the minimum and maximum of the rust integer type `type_name`.
+fn integer_bounds(type_name: &str) -> Option<(i128, i128)> {
+    match type_name {
+        "u8" => Some((u8::MIN.into(), u8::MAX.into())),
+        "u16" => Some((u16::MIN.into(), u16::MAX.into())),
+        "u32" => Some((u32::MIN.into(), u32::MAX.into())),
+        "u64" => Some((u64::MIN.into(), u64::MAX.into())),
+        "i8" => Some((i8::MIN.into(), i8::MAX.into())),
+        "i16" => Some((i16::MIN.into(), i16::MAX.into())),
+        "i32" => Some((i32::MIN.into(), i32::MAX.into())),
+        "i64" => Some((i64::MIN.into(), i64::MAX.into())),
+        _ => None,
+    }
+}
+
+/// Returns the enum of the allowed values of `variable`, named in camel case, e.g. `IP_Routed` becomes `IpRouted`.
+/// Names occurring twice get a number appended.
+fn allowed_values_enum(variable: &StateVariable, unknown_variant: bool) -> TemplateEnum {
+    let mut names: Vec<String> = Vec::new();
+    if unknown_variant {
+        names.push("Unknown".to_string());
+    }
+    let mut variants: Vec<TemplateVariant> = Vec::new();
+
+    for value in &variable.allowed_value_list.allowed_value {
+        let literal = format!("{:?}", value);
+        if variants.iter().any(|variant| variant.literal == literal) {
+            continue;
+        }
+        let base_name = camel_case(value);
+        let mut name = base_name.clone();
+        let mut index = 2;
+        while names.contains(&name) {
+            name = format!("{}{}", base_name, index);
+            index += 1;
+        }
+        names.push(name.clone());
+        variants.push(TemplateVariant { name, literal });
+    }
+
+    TemplateEnum {
+        name: camel_case(&variable.name),
+        variable: variable.name.clone(),
+        variants,
+        unknown_variant,
+    }
+}
+
+/// Returns the rust type of the UPnP `data_type`, see chapter 2.5 of the UPnP Device Architecture.
+/// Times and URIs are kept as `String`, dates, UUIDs and binaries unless configured otherwise in `types`.
+fn rust_type(data_type: &str, types: &TypeOptions) -> Option<RustType> {
+    let glued = |name: &str, deserialize_with: Option<&'static str>, format: Option<&str>| RustType {
+        name: name.to_string(),
+        deserialize_with: deserialize_with.map(DeserializeWith::MultiUse),
+        format: format.map(str::to_string),
+        definition: None,
+    };
+    match (data_type, types.date_time, types.uuid, types.binary) {
+        ("date", DateTimeMapping::Chrono, _, _) => return Some(RustType::plain("chrono::NaiveDate")),
+        ("dateTime", DateTimeMapping::Chrono, _, _) => {
+            return Some(glued(
+                "chrono::NaiveDateTime",
+                Some("deserialize_date_time"),
+                Some(r#"{}.format("%Y-%m-%dT%H:%M:%S")"#),
+            ))
+        }
+        ("dateTime.tz", DateTimeMapping::Chrono, _, _) => {
+            return Some(glued("chrono::DateTime<chrono::FixedOffset>", None, Some("{}.to_rfc3339()")))
+        }
+        ("uuid", _, UuidMapping::Uuid, _) => return Some(RustType::plain("uuid::Uuid")),
+        ("bin.base64", _, _, BinaryMapping::Bytes) => {
+            return Some(glued(
+                "Vec<u8>",
+                Some("deserialize_base64"),
+                Some("base64::Engine::encode(&base64::engine::general_purpose::STANDARD, {})"),
+            ))
+        }
+        ("bin.hex", _, _, BinaryMapping::Bytes) => {
+            return Some(glued("Vec<u8>", Some("deserialize_hex"), Some("hex::encode({})")))
+        }
+        // `0` and `1` like the default values in the descriptions, instead of `false` and `true`
+        ("boolean", _, _, _) => return Some(glued("bool", None, Some("u8::from(*{})"))),
+        _ => {}
+    }
+
+    let name = match (data_type, types.integers) {
+        ("ui1", IntegerMapping::Exact) => Some("u8"),
+        ("ui2", IntegerMapping::Exact) => Some("u16"),
+        ("i1", IntegerMapping::Exact) => Some("i8"),
+        ("i2", IntegerMapping::Exact) => Some("i16"),
+        _ => widened_type(data_type),
+    };
+
+    name.map(RustType::plain)
+}
+
+/// Returns the rust type of the UPnP `data_type` with integers widened to 32 bit and everything textual as `String`.
+fn widened_type(data_type: &str) -> Option<&'static str> {
+    match data_type {
+        "boolean" => Some("bool"),
+        "ui1" | "ui2" | "ui4" => Some("u32"),
+        "ui8" => Some("u64"),
+        "i1" | "i2" | "i4" | "int" => Some("i32"),
+        "i8" => Some("i64"),
+        "r4" => Some("f32"),
+        "r8" | "number" | "fixed.14.4" | "float" => Some("f64"),
+        "char" => Some("char"),
+        "string" | "uuid" | "uri" => Some("String"),
+        "date" | "dateTime" | "dateTime.tz" | "time" | "time.tz" => Some("String"),
+        "bin.base64" | "bin.hex" => Some("String"),
+        _ => None,
+    }
 }
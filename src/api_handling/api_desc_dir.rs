@@ -1,10 +1,14 @@
-use std::collections::HashMap;
-use std::fs;
-use std::fs::File;
-use std::io::Write;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
 use handlebars::Handlebars;
+use serde::Serialize;
 use crate::api_handling::handle_device;
-use crate::api_handling::helper::rustify_string;
+use crate::api_handling::helper::rustify_string_with;
+use crate::api_handling::source::DescriptionSource;
+use crate::config::Config;
+use crate::error::GeneratorError;
+use crate::generator::{GenerationResult, SkippedService};
+use crate::sink::OutputSink;
 
 ///Struct to deserialize the response from "fritz.box/tr64desc.xml" into.
 #[derive(Deserialize, Debug, Default)]
@@ -101,7 +105,7 @@ pub struct Service {
     pub scpd_url: String,
 }
 ///Struct to deserialize the system version part of the response from "fritz.box/tr64desc.xml" into.
-#[derive(Deserialize, Debug, Default)]
+#[derive(Deserialize, Serialize, Debug, Default)]
 pub struct SystemVersion {
     #[serde(rename = "HW")]
     pub hw: u32,
@@ -136,6 +140,12 @@ pub struct ParameterAndType {
     pub parameter_name: String,
     pub parameter_name_rusty: String,
     pub type_name: String,
+    /// Expression putting the borrowed struct field into the request body, e.g. `hex::encode(new_key)`.
+    pub format_argument: String,
+    /// Expression turning the function parameter into the struct field, empty if it's moved as is.
+    pub owned_argument: String,
+    /// Whether the parameter is an `Option` falling back to the default value of its state variable.
+    pub optional: bool,
 }
 
 /// Struct to build response files. `RequestFunction`s get pushed into `request_functions` and assembled later.
@@ -147,6 +157,7 @@ pub struct RequestFile {
 
 /// Represents a request function, `name` is taken directly from the API, `name_rusty` is the same name in proper snake case.
 /// `service_type`, `action_name` and `control_type` are directly taken from the API.
+/// `struct_name` is the name of the request struct, `response_type` the path of the response struct.
 #[derive(Debug, Clone, Serialize)]
 pub struct RequestFunction {
     pub name: String,
@@ -155,20 +166,28 @@ pub struct RequestFunction {
     pub service_type: String,
     pub action_name: String,
     pub control_url: String,
+    pub struct_name: String,
+    pub response_type: String,
+    /// Variant of the generated `AccessRight` enum the action needs, if the access rights list it.
+    pub access_right: Option<&'static str>,
 }
 
 impl RequestFunction {
     pub fn new(name: String,
                service_type: String,
-               control_url: String) -> Self {
+               control_url: String,
+               naming: &BTreeMap<String, String>) -> Self {
 
         RequestFunction {
             name: name.clone(),
-            name_rusty: rustify_string(name.as_str()),
+            name_rusty: rustify_string_with(name.as_str(), naming),
             parameter: Vec::new(),
             service_type: service_type.clone(),
             action_name: name.clone(),
             control_url,
+            struct_name: String::new(),
+            response_type: String::new(),
+            access_right: None,
         }
     }
 
@@ -178,86 +197,107 @@ impl RequestFunction {
 #[derive(Debug)]
 pub struct OutputFiles<'a> {
     pub annotation_string: Vec<String>,
+    /// Deserialize functions the response files use, written into the multi use file.
+    pub glue: BTreeSet<&'static str>,
     pub response_files: Vec<ResponseFile>,
     pub request_files: Vec<RequestFile>,
     pub response_output_folder: String,
     pub request_output_folder: String,
     pub prefix: String,
-    pub handlebars: &'a Handlebars<'a>
+    pub handlebars: &'a Handlebars<'a>,
+    pub config: &'a Config,
+    pub written_files: Vec<PathBuf>,
+    pub warnings: Vec<String>,
+    pub inputs: Vec<String>,
+    pub skipped_services: Vec<SkippedService>,
 }
 impl <'a> OutputFiles<'a> {
-    pub fn new(handlebars: &'a Handlebars) -> Self {
+    pub fn new(handlebars: &'a Handlebars, config: &'a Config) -> Self {
         OutputFiles {
             annotation_string: vec![],
+            glue: BTreeSet::new(),
             response_files: vec![],
             request_files: vec![],
             response_output_folder: "response_output".to_string(),
             request_output_folder: "request_output".to_string(),
             prefix: "".to_string(),
-            handlebars
+            handlebars,
+            config,
+            written_files: vec![],
+            warnings: vec![],
+            inputs: vec![],
+            skipped_services: vec![],
         }
     }
 
     /// Calls all functions needed to actually create the output files.
-    fn create_files(&self) {
-        self.create_output_folders();
-        self.write_annotation_file();
-        self.write_mod_files();
-        self.write_requests_files();
-        self.write_responses_files();
+    fn create_files(&mut self, sink: &mut dyn OutputSink) -> Result<(), GeneratorError> {
+        self.check_duplicate_files();
+        self.write_annotation_file(sink)?;
+        self.write_request_multi_use_file(sink)?;
+        self.write_mod_files(sink)?;
+        self.write_requests_files(sink)?;
+        self.write_responses_files(sink)?;
+        Ok(())
     }
-    /// Creates output folders. If they are already present, do nothing. On every other error, panic.
-    fn create_output_folders(&self) {
-        if let Err(e) = fs::create_dir_all(&self.response_output_folder) {
-            if e.kind() != std::io::ErrorKind::AlreadyExists {
-                println!("{}", e);
-                panic!();
-            }
-        };
-        if let Err(e) = fs::create_dir_all(&self.request_output_folder) {
-            if e.kind() != std::io::ErrorKind::AlreadyExists {
-                println!("{}", e);
-                panic!();
-            }
-        };
+
+    /// Writes `content` to `path` via `sink` and remembers the file.
+    fn write_file(&mut self, sink: &mut dyn OutputSink, path: String, content: &str) -> Result<(), GeneratorError> {
+        let path = PathBuf::from(path);
+        if let Err(source) = sink.write_file(&path, content) {
+            return Err(GeneratorError::Io { path, source });
+        }
+        self.written_files.push(path);
+        Ok(())
     }
 
-    /// Writes the annotation file to the disk.
-    fn write_annotation_file(&self) {
-        let mut file = File::create(format!(
-            "{}/{}multi_use.rs",
-            &self.response_output_folder, self.prefix
-        ))
-        .unwrap();
+    /// Renders `template` with `data`.
+    fn render<T: Serialize>(&self, template: &str, data: &T) -> Result<String, GeneratorError> {
+        self.handlebars.render(template, data).map_err(|source| GeneratorError::TemplateRender {
+            template: template.to_string(),
+            source,
+        })
+    }
 
-        let mut annotation_data: HashMap<&str, Vec<String>> = HashMap::new();
-        annotation_data.insert("actions", self.annotation_string.clone());
+    /// Writes the annotation file.
+    fn write_annotation_file(&mut self, sink: &mut dyn OutputSink) -> Result<(), GeneratorError> {
+        let mut annotation_data: HashMap<&str, serde_json::Value> = HashMap::new();
+        annotation_data.insert("actions", serde_json::json!(self.annotation_string));
+        let glue: BTreeMap<&str, bool> = self.glue.iter().map(|name| (*name, true)).collect();
+        annotation_data.insert("glue", serde_json::json!(glue));
 
-        let file_content = self.handlebars.render("multi_use", &annotation_data).unwrap();
+        let file_content = self.render("multi_use", &annotation_data)?;
 
-        file.write_all(
-            file_content.as_bytes(),
-        )
-        .unwrap();
+        let path = format!("{}/{}multi_use.rs", &self.response_output_folder, self.prefix);
+        self.write_file(sink, path, &file_content)
     }
 
-    /// Writes all the response files to the disk.
-    fn write_responses_files(&self) {
-        for response_file in &self.response_files {
-            let mut file = File::create(format!(
+    /// Writes the file shared by the request files, building the SOAP envelopes.
+    /// Template folders written before the `request_multi_use` template don't need it and are left as they are.
+    fn write_request_multi_use_file(&mut self, sink: &mut dyn OutputSink) -> Result<(), GeneratorError> {
+        if !self.handlebars.has_template("request_multi_use") {
+            return Ok(());
+        }
+        let file_content = self.render("request_multi_use", &HashMap::<&str, String>::new())?;
+
+        let path = format!("{}/{}multi_use.rs", &self.request_output_folder, self.prefix);
+        self.write_file(sink, path, &file_content)
+    }
+
+    /// Writes all the response files.
+    fn write_responses_files(&mut self, sink: &mut dyn OutputSink) -> Result<(), GeneratorError> {
+        for response_file in std::mem::take(&mut self.response_files) {
+            let path = format!(
                 "{}/{}{}.rs",
                 &self.response_output_folder, self.prefix, response_file.name
-            ))
-            .unwrap();
-            file.write_all(response_file.content.as_bytes())
-                .unwrap();
+            );
+            self.write_file(sink, path, &response_file.content)?;
         }
+        Ok(())
     }
 
     /// Writes the mod.rs files into the two folders.
-    fn write_mod_files(&self) {
-        let mut file = File::create(format!("{}/mod.rs", &self.response_output_folder)).unwrap();
-
+    fn write_mod_files(&mut self, sink: &mut dyn OutputSink) -> Result<(), GeneratorError> {
         let mut file_name_vec = vec![format!("{}multi_use", self.prefix)];
         for response_file in &self.response_files {
             file_name_vec.push(format!("{}{}", self.prefix, response_file.name));
@@ -267,66 +307,112 @@ impl <'a> OutputFiles<'a> {
 
         let mut templated_data: HashMap<&str, Vec<String>> = HashMap::new();
         templated_data.insert("mod_files", file_name_vec.clone());
-        let file_content = self.handlebars.render("mod",&templated_data).unwrap();
-        file.write_all(file_content.as_bytes()).unwrap();
+        let file_content = self.render("mod", &templated_data)?;
+        let path = format!("{}/mod.rs", &self.response_output_folder);
+        self.write_file(sink, path, &file_content)?;
 
         file_name_vec.clear();
-        let mut file = File::create(format!("{}/mod.rs", &self.request_output_folder)).unwrap();
-
+        if self.handlebars.has_template("request_multi_use") {
+            file_name_vec.push(format!("{}multi_use", self.prefix));
+        }
         for request_file in &self.request_files {
             file_name_vec.push(format!("{}{}", self.prefix, request_file.name));
         }
         file_name_vec.sort();
         file_name_vec.dedup();
         templated_data.insert("mod_files", file_name_vec.clone());
-        let file_content = self.handlebars.render("mod", &templated_data).unwrap();
-        file.write_all(file_content.as_bytes()).unwrap();
+        let file_content = self.render("mod", &templated_data)?;
+        let path = format!("{}/mod.rs", &self.request_output_folder);
+        self.write_file(sink, path, &file_content)
     }
 
-    /// Writes all request files to disk.
-    fn write_requests_files(&self) {
-        for request_file in &self.request_files {
-            let mut file = File::create(format!(
+    /// Writes all request files.
+    fn write_requests_files(&mut self, sink: &mut dyn OutputSink) -> Result<(), GeneratorError> {
+        for request_file in std::mem::take(&mut self.request_files) {
+            let mut templated_data: HashMap<&str, serde_json::Value> = HashMap::new();
+            templated_data.insert("request_functions", serde_json::json!(request_file.request_functions));
+            templated_data.insert("multi_use", serde_json::json!(format!("{}multi_use", self.prefix)));
+            templated_data.insert("module", serde_json::json!(format!("{}{}", self.prefix, request_file.name)));
+            templated_data.insert(
+                "client",
+                serde_json::json!({
+                    "blocking": self.config.client.blocking(),
+                    "async": self.config.client.asynchronous(),
+                }),
+            );
+            let file_content = self.render("request_function", &templated_data)?;
+
+            let path = format!(
                 "{}/{}{}.rs",
                 &self.request_output_folder, self.prefix, &request_file.name
-            ))
-            .unwrap();
+            );
+            self.write_file(sink, path, &file_content)?;
+        }
+        Ok(())
+    }
 
-            let mut templated_data: HashMap<&str, Vec<RequestFunction>> = HashMap::new();
-            templated_data.insert("request_functions", request_file.request_functions.to_vec());
-            let file_content = self.handlebars.render("request_function", &templated_data).unwrap();
+    /// Warns about services sharing a file name, only the last of them ends up in the output.
+    fn check_duplicate_files(&mut self) {
+        let mut names: Vec<&String> = self.request_files.iter().map(|request_file| &request_file.name).collect();
+        names.sort();
+        let mut duplicates: Vec<&String> = names.windows(2).filter(|pair| pair[0] == pair[1]).map(|pair| pair[0]).collect();
+        duplicates.dedup();
 
-            file.write_all(file_content.as_bytes()).unwrap();
+        for name in duplicates {
+            self.warnings.push(format!(
+                "multiple services generate `{}{}.rs`, only the last one is kept",
+                self.prefix, name
+            ));
+        }
+    }
+}
+
+impl Device {
+    /// Returns the services of this device and all contained devices, in the order they are handled.
+    pub fn services(&self) -> Vec<&Service> {
+        let mut services = vec![];
+        let mut devices_to_handle = vec![self];
 
+        while let Some(device) = devices_to_handle.pop() {
+            services.extend(device.service_list.service.iter());
+            devices_to_handle.extend(device.device_list.device.iter());
         }
+
+        services
     }
 }
 
 impl ApiDescDir {
     /// Generates request and response files from the FritzBox TR-064 API.
+    /// The SCPD files are read from `source`, the output does not depend on whether it is a FRITZ!Box or a snapshot.
+    /// The files are written into `sink`, in the folders configured in `config`, e.g. `tr064_requests`.
     pub fn generate_files(
         &self,
-        address: &str,
-        responses_output_folder: String,
-        request_output_folder: String,
+        source: &DescriptionSource,
         prefix: Option<String>,
-        handlebars: &Handlebars
-    ) {
-        let mut output_files = OutputFiles::new(handlebars);
-        let prefix = if prefix.is_some() {
-            format!("{}_", prefix.unwrap())
+        config: &Config,
+        handlebars: &Handlebars,
+        sink: &mut dyn OutputSink,
+    ) -> Result<GenerationResult, GeneratorError> {
+        let mut output_files = OutputFiles::new(handlebars, config);
+        let prefix = if let Some(prefix) = prefix {
+            format!("{}_", prefix)
         } else {
             String::from("")
         };
-        output_files.response_output_folder =
-            format!("output/{}{}", prefix, responses_output_folder);
-        output_files.request_output_folder = format!("output/{}{}", prefix, request_output_folder);
+        output_files.response_output_folder = format!("{}{}", prefix, config.output.responses_folder);
+        output_files.request_output_folder = format!("{}{}", prefix, config.output.requests_folder);
         output_files.prefix = prefix;
 
-        handle_device(&self.device, address, &mut output_files);
-
-        output_files.create_files();
-    }
+        handle_device(&self.device, source, &mut output_files)?;
 
+        output_files.create_files(sink)?;
 
+        Ok(GenerationResult {
+            files: output_files.written_files,
+            warnings: output_files.warnings,
+            inputs: output_files.inputs,
+            skipped_services: output_files.skipped_services,
+        })
+    }
 }
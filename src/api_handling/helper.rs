@@ -1,3 +1,13 @@
+use std::collections::BTreeMap;
+
+/// Returns the name configured for `input` in `naming`, or `rustify_string(input)` if there is none.
+pub fn rustify_string_with(input: &str, naming: &BTreeMap<String, String>) -> String {
+    match naming.get(input) {
+        Some(name) => name.clone(),
+        None => rustify_string(input),
+    }
+}
+
 /// Modifies the supplied `input` to generate proper snake case.
 pub fn rustify_string(input: &str) -> String {
     input
@@ -79,4 +89,56 @@ pub fn rustify_string(input: &str) -> String {
             }
         })
         .collect()
-}
\ No newline at end of file
+}
+
+/// Formats seconds since the unix epoch as an RFC 3339 UTC timestamp, e.g. `2024-04-02T13:37:00Z`.
+pub fn format_unix_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let seconds_of_day = timestamp % 86_400;
+
+    // civil date from days since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3_600,
+        seconds_of_day % 3_600 / 60,
+        seconds_of_day % 60
+    )
+}
+
+/// Turns `input` into an upper camel case identifier, e.g. `IP_Routed` into `IpRouted` and `802.11n` into `V80211n`.
+/// Parts written in capitals only are lowercased after their first letter, empty input becomes `Empty`.
+pub fn camel_case(input: &str) -> String {
+    let mut name: String = input
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .map(|part| {
+            let mut chars = part.chars();
+            let first = chars.next().map(|c| c.to_ascii_uppercase());
+            let rest: String = if part.chars().any(|c| c.is_ascii_lowercase()) {
+                chars.collect()
+            } else {
+                chars.map(|c| c.to_ascii_lowercase()).collect()
+            };
+            first.into_iter().chain(rest.chars()).collect::<String>()
+        })
+        .collect();
+
+    if name.is_empty() {
+        name = String::from("Empty");
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, 'V');
+    }
+    name
+}
@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use crate::api_handling::api_desc::ApiDesc;
+use crate::api_handling::api_desc_dir::ApiDescDir;
+use crate::api_handling::source::DescriptionSource;
+use crate::error::GeneratorError;
+
+/// Summary of a description file, used to inspect and compare FRITZ!OS versions.
+#[derive(Debug, Default)]
+pub struct DescriptionSummary {
+    pub model_name: String,
+    pub firmware: String,
+    pub services: BTreeMap<ServiceKey, ServiceSummary>,
+}
+
+/// Identifies a service of a description. A service type can occur on several devices, e.g. `WLANConfiguration` once
+/// per WLAN, the control URL tells them apart.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ServiceKey {
+    pub service_type: String,
+    pub control_url: String,
+}
+
+impl Display for ServiceKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.service_type, self.control_url)
+    }
+}
+
+/// Summary of a service and its actions.
+#[derive(Debug, Default, PartialEq)]
+pub struct ServiceSummary {
+    pub scpd_url: String,
+    /// Actions by name.
+    pub actions: BTreeMap<String, ActionSummary>,
+}
+
+/// Arguments of an action, each with the UPnP data type of its related state variable.
+#[derive(Debug, Default, PartialEq)]
+pub struct ActionSummary {
+    pub in_arguments: Vec<(String, String)>,
+    pub out_arguments: Vec<(String, String)>,
+}
+
+impl Display for ActionSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let format_arguments = |arguments: &Vec<(String, String)>| {
+            arguments
+                .iter()
+                .map(|(name, data_type)| format!("{}: {}", name, data_type))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        write!(
+            f,
+            "({}) -> ({})",
+            format_arguments(&self.in_arguments),
+            format_arguments(&self.out_arguments)
+        )
+    }
+}
+
+/// Reads `description_file` and all SCPD files it references from `source`.
+pub fn summarize(source: &DescriptionSource, description_file: &str) -> Result<DescriptionSummary, GeneratorError> {
+    let api_desc_dir: ApiDescDir = source.fetch_description(description_file)?;
+    let mut summary = DescriptionSummary {
+        model_name: api_desc_dir.device.model_name.clone(),
+        firmware: api_desc_dir.system_version.display.clone(),
+        services: BTreeMap::new(),
+    };
+
+    for service in api_desc_dir.device.services() {
+        let scpd: ApiDesc = source
+            .fetch_description(&service.scpd_url)
+            .map_err(|e| GeneratorError::Service {
+                service_type: service.service_type.clone(),
+                scpd_url: service.scpd_url.clone(),
+                source: Box::new(e),
+            })?;
+        let mut service_summary = ServiceSummary {
+            scpd_url: service.scpd_url.clone(),
+            actions: BTreeMap::new(),
+        };
+
+        for action in &scpd.action_list.action {
+            let mut action_summary = ActionSummary::default();
+            for argument in &action.argument_list.argument {
+                let data_type = scpd
+                    .service_state_table
+                    .state_variable
+                    .iter()
+                    .find(|variable| variable.name == argument.related_state_variable)
+                    .map_or_else(|| String::from("?"), |variable| variable.data_type.clone());
+                if argument.direction == "in" {
+                    action_summary.in_arguments.push((argument.name.clone(), data_type));
+                } else {
+                    action_summary.out_arguments.push((argument.name.clone(), data_type));
+                }
+            }
+            service_summary.actions.insert(action.name.clone(), action_summary);
+        }
+
+        let key = ServiceKey {
+            service_type: service.service_type.clone(),
+            control_url: service.control_url.clone(),
+        };
+        summary.services.insert(key, service_summary);
+    }
+
+    Ok(summary)
+}
+
+/// Lists added (`+`), removed (`-`) and changed (`~`) services and actions between `old` and `new`.
+/// A service whose control URL changed is listed as removed and added. Returns an empty `Vec` if both are equal.
+pub fn diff(old: &DescriptionSummary, new: &DescriptionSummary) -> Vec<String> {
+    let mut lines = vec![];
+
+    for (key, old_service) in &old.services {
+        match new.services.get(key) {
+            None => lines.push(format!("- {}", key)),
+            Some(new_service) if new_service != old_service => {
+                lines.push(format!("~ {}", key));
+                for (name, old_action) in &old_service.actions {
+                    match new_service.actions.get(name) {
+                        None => lines.push(format!("    - {}{}", name, old_action)),
+                        Some(new_action) if new_action != old_action => {
+                            lines.push(format!("    ~ {}{}", name, old_action));
+                            lines.push(format!("      {}{}", name, new_action));
+                        }
+                        Some(_) => {}
+                    }
+                }
+                for (name, new_action) in &new_service.actions {
+                    if !old_service.actions.contains_key(name) {
+                        lines.push(format!("    + {}{}", name, new_action));
+                    }
+                }
+            }
+            Some(_) => {}
+        }
+    }
+    for key in new.services.keys() {
+        if !old.services.contains_key(key) {
+            lines.push(format!("+ {}", key));
+        }
+    }
+
+    lines
+}
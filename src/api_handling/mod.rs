@@ -1,9 +1,15 @@
 use crate::api_handling::api_desc::ApiDesc;
 use crate::api_handling::api_desc_dir::{Device, OutputFiles};
+use crate::api_handling::source::DescriptionSource;
+use crate::error::GeneratorError;
+use crate::generator::SkippedService;
 
 pub mod api_desc;
 pub mod api_desc_dir;
 pub mod helper;
+pub mod inspect;
+pub mod snapshot;
+pub mod source;
 
 #[derive(Debug, Default, Serialize)]
 pub struct TemplateAction {
@@ -15,29 +21,85 @@ pub struct TemplateAction {
 pub struct Field {
     name: String,
     xml_name: String,
-    r#type: String
+    r#type: String,
+    /// Path of the function deserializing the field, empty if the type deserializes itself.
+    deserialize_with: String,
+}
+
+/// Enum generated from the `allowedValueList` of the state variable `variable`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TemplateEnum {
+    name: String,
+    variable: String,
+    variants: Vec<TemplateVariant>,
+    unknown_variant: bool,
+}
+
+/// Variant of a `TemplateEnum`, `literal` is the value sent by the FRITZ!Box as rust string literal.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TemplateVariant {
+    name: String,
+    literal: String,
+}
+
+/// Newtype generated from the `allowedValueRange` of the state variable `variable`, wrapping the integer `type`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TemplateRange {
+    name: String,
+    variable: String,
+    r#type: String,
+    minimum: String,
+    maximum: String,
+    step: String,
+    check_step: bool,
+}
+
+/// Constant `name` holding the `defaultValue` of the state variable `variable` as rust string literal.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TemplateDefault {
+    name: String,
+    variable: String,
+    literal: String,
 }
 
 /// Handles all services of a device and all contained devices.
-fn handle_device(device: &Device, address: &str, output_files: &mut OutputFiles) {
-    let mut devices_to_handle = vec![device];
-
-    while let Some(device) = devices_to_handle.pop() {
-        for service in &device.service_list.service {
-            let resp = reqwest::blocking::get(format!("{}{}", address, service.scpd_url).as_str())
-                .unwrap()
-                .text()
-                .unwrap();
-            let scdp: ApiDesc = serde_xml_rs::from_str(&resp).unwrap();
-            service.service_type.split(':').nth(3).unwrap();
-            scdp.fill_output_files(
-                output_files,
-                service.service_type.split(':').nth(3).unwrap(),
-                &service.control_url,
-                &service.service_type,
-            );
+/// With `continue_on_error`, failing services are left out and collected in `OutputFiles::skipped_services`.
+fn handle_device(device: &Device, source: &DescriptionSource, output_files: &mut OutputFiles) -> Result<(), GeneratorError> {
+    for service in device.services() {
+        if !output_files.config.services.is_included(&service.service_type) {
+            continue;
         }
+        output_files.inputs.push(source.location(&service.scpd_url));
+        let result = source
+            .fetch_description::<ApiDesc>(&service.scpd_url)
+            .and_then(|scdp| {
+                scdp.fill_output_files(
+                    output_files,
+                    service.service_type.split(':').nth(3).unwrap_or(&service.service_type),
+                    &service.control_url,
+                    &service.service_type,
+                )
+            })
+            .map_err(|e| GeneratorError::Service {
+                service_type: service.service_type.clone(),
+                scpd_url: service.scpd_url.clone(),
+                source: Box::new(e),
+            });
 
-        devices_to_handle.extend(device.device_list.device.iter());
+        if let Err(error) = result {
+            if !output_files.config.continue_on_error {
+                return Err(error);
+            }
+            output_files.skipped_services.push(SkippedService {
+                service_type: service.service_type.clone(),
+                scpd_url: service.scpd_url.clone(),
+                error: match error {
+                    GeneratorError::Service { source, .. } => *source,
+                    error => error,
+                },
+            });
+        }
     }
+
+    Ok(())
 }
@@ -0,0 +1,95 @@
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+use crate::api_handling::api_desc_dir::{ApiDescDir, SystemVersion};
+use crate::api_handling::helper::format_unix_timestamp;
+use crate::api_handling::source::{parse_description, DescriptionSource};
+use crate::error::GeneratorError;
+
+/// Name of the manifest file written into the root of every snapshot.
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Describes which box and firmware a snapshot was taken from.
+#[derive(Debug, Serialize)]
+pub struct SnapshotManifest {
+    #[serde(rename = "modelName")]
+    pub model_name: String,
+    #[serde(rename = "systemVersion")]
+    pub system_version: SystemVersion,
+    /// Fetch time in UTC, formatted as RFC 3339.
+    #[serde(rename = "fetchTime")]
+    pub fetch_time: String,
+    /// Paths of all mirrored files, relative to the snapshot folder.
+    pub files: Vec<String>,
+}
+
+/// Mirrors the `description_files` and every SCPD they reference from `source` into `folder`.
+/// The URL paths are preserved, so the folder can be used as `DescriptionSource::Snapshot` afterwards.
+pub fn take_snapshot(
+    source: &DescriptionSource,
+    description_files: &[&str],
+    folder: &Path,
+) -> Result<SnapshotManifest, GeneratorError> {
+    let fetch_time = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let mut manifest = SnapshotManifest {
+        model_name: String::new(),
+        system_version: SystemVersion::default(),
+        fetch_time: format_unix_timestamp(fetch_time),
+        files: vec![],
+    };
+
+    for description_file in description_files {
+        let resp = source.fetch(description_file)?;
+        mirror_file(folder, description_file, &resp)?;
+        manifest.files.push(description_file.trim_start_matches('/').to_string());
+
+        let api_desc_dir: ApiDescDir = parse_description(&resp, &source.location(description_file))?;
+        for service in api_desc_dir.device.services() {
+            let scpd_path = service.scpd_url.trim_start_matches('/').to_string();
+            if manifest.files.contains(&scpd_path) {
+                continue;
+            }
+            mirror_file(folder, &service.scpd_url, &source.fetch(&service.scpd_url)?)?;
+            manifest.files.push(scpd_path);
+        }
+
+        if manifest.model_name.is_empty() {
+            manifest.model_name = api_desc_dir.device.model_name;
+            manifest.system_version = api_desc_dir.system_version;
+        }
+    }
+
+    let manifest_content = serde_json::to_string_pretty(&manifest).expect("the manifest only contains JSON compatible types");
+    write_file(&folder.join(MANIFEST_FILE_NAME), &manifest_content)?;
+
+    Ok(manifest)
+}
+
+/// Writes `content` to the location of `url_path` inside `folder`, creating missing folders on the way.
+fn mirror_file(folder: &Path, url_path: &str, content: &str) -> Result<(), GeneratorError> {
+    let relative_path = PathBuf::from(url_path.trim_start_matches('/'));
+    let path = folder.join(&relative_path);
+    if relative_path.components().any(|component| !matches!(component, Component::Normal(_))) {
+        return Err(GeneratorError::Io {
+            path,
+            source: io::Error::new(io::ErrorKind::InvalidInput, "refusing to write outside of the snapshot folder"),
+        });
+    }
+
+    write_file(&path, content)
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), GeneratorError> {
+    let result = match path.parent() {
+        Some(parent) => fs::create_dir_all(parent).and_then(|_| fs::write(path, content)),
+        None => fs::write(path, content),
+    };
+
+    result.map_err(|source| GeneratorError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
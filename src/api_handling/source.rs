@@ -0,0 +1,82 @@
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use serde::de::DeserializeOwned;
+use crate::error::GeneratorError;
+use crate::tls::{self, Fingerprint};
+
+/// Where the device description and the SCPD files are read from.
+#[derive(Debug, Clone)]
+pub enum DescriptionSource {
+    /// Fetch everything over HTTP from a FRITZ!Box, e.g. `http://fritz.box:49000`.
+    Http(String),
+    /// Fetch everything over HTTPS, e.g. from `https://fritz.box:49443`, accepting only the certificates with one of
+    /// the `fingerprints`.
+    Https {
+        address: String,
+        fingerprints: Vec<Fingerprint>,
+    },
+    /// Read everything from a folder that mirrors the URL paths of a FRITZ!Box, e.g. `snapshot/tr64desc.xml`.
+    Snapshot(PathBuf),
+}
+
+impl DescriptionSource {
+    /// Returns the full location of `path`, the URL or the file path inside the snapshot folder.
+    pub fn location(&self, path: &str) -> String {
+        match self {
+            DescriptionSource::Http(address) | DescriptionSource::Https { address, .. } => {
+                format!("{}{}", address, path)
+            }
+            DescriptionSource::Snapshot(folder) => folder.join(path.trim_start_matches('/')).display().to_string(),
+        }
+    }
+
+    /// Returns the content of the description found at `path`, e.g. `/tr64desc.xml` or a `Service::scpd_url`.
+    pub fn fetch(&self, path: &str) -> Result<String, GeneratorError> {
+        let content = match self {
+            DescriptionSource::Http(address) => reqwest::blocking::get(format!("{}{}", address, path).as_str())
+                .and_then(|resp| resp.error_for_status())
+                .and_then(|resp| resp.text())
+                .map_err(|e| e.into()),
+            DescriptionSource::Https { address, fingerprints } => tls::blocking_client(fingerprints)
+                .and_then(|client| client.get(format!("{}{}", address, path)).send())
+                .and_then(|resp| resp.error_for_status())
+                .and_then(|resp| resp.text())
+                .map_err(|e| e.into()),
+            DescriptionSource::Snapshot(folder) => {
+                fs::read_to_string(folder.join(path.trim_start_matches('/'))).map_err(|e| e.into())
+            }
+        };
+
+        content.map_err(|source| GeneratorError::Fetch {
+            location: self.location(path),
+            source,
+        })
+    }
+
+    /// Fetches the description found at `path` and deserializes it.
+    pub fn fetch_description<T: DeserializeOwned>(&self, path: &str) -> Result<T, GeneratorError> {
+        parse_description(&self.fetch(path)?, &self.location(path))
+    }
+}
+
+impl FromStr for DescriptionSource {
+    type Err = std::convert::Infallible;
+
+    /// Parses `http://` and `https://` locations as `Http`, everything else as a `Snapshot` folder.
+    fn from_str(location: &str) -> Result<Self, Self::Err> {
+        if location.starts_with("http://") || location.starts_with("https://") {
+            Ok(DescriptionSource::Http(location.trim_end_matches('/').to_string()))
+        } else {
+            Ok(DescriptionSource::Snapshot(PathBuf::from(location)))
+        }
+    }
+}
+
+/// Deserializes the description `content` read from `location`.
+pub fn parse_description<T: DeserializeOwned>(content: &str, location: &str) -> Result<T, GeneratorError> {
+    serde_xml_rs::from_str(content).map_err(|source| GeneratorError::Parse {
+        location: location.to_string(),
+        source,
+    })
+}
@@ -0,0 +1,28 @@
+//! Generating the files from a build script.
+//!
+//! ```no_run
+//! // build.rs, in `fn main()`
+//! let out_dir = std::env::var("OUT_DIR").unwrap();
+//! fritz_box_tr064_igd_api_files_generator::build_script::generate("snapshots/7.57", out_dir).unwrap();
+//! ```
+//! ```ignore
+//! // lib.rs
+//! include!(concat!(env!("OUT_DIR"), "/fritz_box_api.rs"));
+//! ```
+
+use std::path::Path;
+use crate::api_handling::source::DescriptionSource;
+use crate::error::GeneratorError;
+use crate::generator::{GenerationResult, Generator};
+
+/// Name of the root module `generate` writes into the output folder.
+pub const ROOT_MODULE_FILE_NAME: &str = "fritz_box_api.rs";
+
+/// Generates the files of the `snapshot` folder with the default configuration into `out_dir/fritz_box_api.rs`
+/// and prints `cargo:rerun-if-changed` for every description file read.
+/// Use `Generator::generate_root_module` for other configurations.
+pub fn generate(snapshot: impl AsRef<Path>, out_dir: impl AsRef<Path>) -> Result<GenerationResult, GeneratorError> {
+    Generator::new()
+        .source(DescriptionSource::Snapshot(snapshot.as_ref().to_path_buf()))
+        .generate_root_module(out_dir.as_ref().join(ROOT_MODULE_FILE_NAME))
+}
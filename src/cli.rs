@@ -0,0 +1,305 @@
+use std::path::PathBuf;
+use clap::{Args, Parser, Subcommand};
+use fritz_box_tr064_igd_api_files_generator::api_handling::source::DescriptionSource;
+use fritz_box_tr064_igd_api_files_generator::config::{
+    AllowedValuesMapping, BinaryMapping, ClientFlavour, Config, DateTimeMapping, Description, IntegerMapping, RangeMapping,
+    SourceConfig, UuidMapping,
+};
+use fritz_box_tr064_igd_api_files_generator::tls::Fingerprint;
+use fritz_box_tr064_igd_api_files_generator::GeneratorError;
+
+/// Generates rust source code files for the AVM FRITZ!Box TR-064 and IGD APIs.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Generate request and response files.
+    Generate(Box<GenerateArgs>),
+    /// Mirror the descriptions of a FRITZ!Box into a snapshot folder.
+    Snapshot(SnapshotArgs),
+    /// List the services and actions of a FRITZ!Box or snapshot.
+    Inspect(InspectArgs),
+    /// Compare the services and actions of two FRITZ!Boxes or snapshots, exits with 1 if they differ.
+    Diff(DiffArgs),
+}
+
+/// Overrides the `[source]` keys of the configuration file.
+#[derive(Debug, Args)]
+pub struct SourceArgs {
+    /// Path of the configuration file [default: fritzgen.toml if present]
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+    /// Address of the FRITZ!Box, use an IP if `fritz.box` can't be resolved [default: http://fritz.box:49000]
+    #[arg(long)]
+    pub address: Option<String>,
+    /// Read the descriptions from this snapshot folder instead of `--address`.
+    #[arg(long, conflicts_with = "address")]
+    pub snapshot: Option<PathBuf>,
+    /// Description files to process [default: tr064,igd]
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub descriptions: Option<Vec<Description>>,
+    #[command(flatten)]
+    pub tls: TlsArgs,
+}
+
+impl SourceArgs {
+    /// Loads the configuration file and applies the given overrides.
+    pub fn config(&self) -> Result<Config, GeneratorError> {
+        let mut config = Config::load(self.config.as_deref())?;
+        if let Some(address) = &self.address {
+            config.source.address = address.clone();
+            config.source.snapshot = None;
+        }
+        if let Some(snapshot) = &self.snapshot {
+            config.source.snapshot = Some(snapshot.clone());
+        }
+        if let Some(descriptions) = &self.descriptions {
+            config.source.descriptions = descriptions.clone();
+        }
+        self.tls.apply(&mut config.source);
+
+        Ok(config)
+    }
+}
+
+/// Pins the certificate of a FRITZ!Box reached over HTTPS, e.g. `--address https://fritz.box:49443`.
+#[derive(Debug, Args)]
+pub struct TlsArgs {
+    /// SHA-256 fingerprint of the certificate to accept, may be repeated.
+    #[arg(long = "fingerprint", value_name = "SHA256")]
+    pub fingerprints: Vec<Fingerprint>,
+    /// PEM file with the certificates to accept, e.g. the one exported from the FRITZ!Box.
+    #[arg(long, value_name = "PEM")]
+    pub certificate: Option<PathBuf>,
+}
+
+impl TlsArgs {
+    /// Adds the given certificates to `source`.
+    pub fn apply(&self, source: &mut SourceConfig) {
+        source.fingerprints.extend(self.fingerprints.iter().copied());
+        if let Some(certificate) = &self.certificate {
+            source.certificate = Some(certificate.clone());
+        }
+    }
+}
+
+/// Overrides the keys of the configuration file.
+#[derive(Debug, Args)]
+pub struct GenerateArgs {
+    #[command(flatten)]
+    pub source: SourceArgs,
+    /// Print the resolved configuration and exit.
+    #[arg(long)]
+    pub explain: bool,
+    /// Skip services that fail instead of aborting, exits with 2 if any were skipped.
+    #[arg(long)]
+    pub continue_on_error: bool,
+    /// Root folder of the generated files [default: output]
+    #[arg(long)]
+    pub output: Option<String>,
+    /// Name of the requests output folder [default: requests]
+    #[arg(long)]
+    pub requests_folder: Option<String>,
+    /// Name of the responses output folder [default: responses]
+    #[arg(long)]
+    pub responses_folder: Option<String>,
+    /// Folder containing the handlebars templates [default: built-in templates]
+    #[arg(long)]
+    pub templates: Option<PathBuf>,
+    /// TR-064 folder and files prefix [default: tr064]
+    #[arg(long)]
+    pub tr064_prefix: Option<String>,
+    /// IGD folder and files prefix [default: igd]
+    #[arg(long)]
+    pub igd_prefix: Option<String>,
+    /// Only generate these services, by service type or name, e.g. `WLANConfiguration`.
+    #[arg(long, value_delimiter = ',')]
+    pub include: Option<Vec<String>>,
+    /// Don't generate these services, by service type or name.
+    #[arg(long, value_delimiter = ',')]
+    pub exclude: Option<Vec<String>>,
+    /// Rust types of `ui1`, `ui2`, `i1` and `i2` [default: widened]
+    #[arg(long, value_enum)]
+    pub integers: Option<IntegerMapping>,
+    /// Rust types of `date`, `dateTime` and `dateTime.tz` [default: string]
+    #[arg(long, value_enum)]
+    pub date_time: Option<DateTimeMapping>,
+    /// Rust type of `uuid` [default: string]
+    #[arg(long, value_enum)]
+    pub uuid: Option<UuidMapping>,
+    /// Rust type of `bin.base64` and `bin.hex` [default: string]
+    #[arg(long, value_enum)]
+    pub binary: Option<BinaryMapping>,
+    /// Rust type of `string` state variables with allowed values [default: string]
+    #[arg(long, value_enum)]
+    pub allowed_values: Option<AllowedValuesMapping>,
+    /// Rust type of integer state variables with an allowed range [default: plain]
+    #[arg(long, value_enum)]
+    pub ranges: Option<RangeMapping>,
+    /// Leave out the `Unknown(String)` variant of the generated enums, failing on values the description doesn't list.
+    #[arg(long)]
+    pub no_unknown_variant: bool,
+    /// Take arguments with a default value as `Option`, `None` sends the default.
+    #[arg(long)]
+    pub optional_defaults: bool,
+    /// Generate a client with a method per action, see `[client]`.
+    #[arg(long)]
+    pub client: bool,
+    /// Blocking, async or both clients, implies `--client`.
+    #[arg(long, value_enum)]
+    pub client_flavour: Option<ClientFlavour>,
+    /// Let the clients pin the certificate of the FRITZ!Box for HTTPS, implies `--client`.
+    #[arg(long)]
+    pub client_tls: bool,
+    /// Map a UPnP data type to a rust type, e.g. `ui1=u8`. Can be given multiple times.
+    #[arg(long, value_parser = parse_key_value)]
+    pub type_mapping: Vec<(String, String)>,
+    /// Use a rust name for an API name, e.g. `NewX_AVM-DE_TAM=new_tam`. Can be given multiple times.
+    #[arg(long, value_parser = parse_key_value)]
+    pub naming: Vec<(String, String)>,
+    /// TOML file with the rights needed by the actions, added to `[access_rights]`.
+    #[arg(long)]
+    pub access_rights: Option<PathBuf>,
+}
+
+impl GenerateArgs {
+    /// Loads the configuration file and applies the given overrides.
+    pub fn config(&self) -> Result<Config, GeneratorError> {
+        let mut config = self.source.config()?;
+        if self.continue_on_error {
+            config.continue_on_error = true;
+        }
+        if let Some(output) = &self.output {
+            config.output.folder = output.clone();
+        }
+        if let Some(requests_folder) = &self.requests_folder {
+            config.output.requests_folder = requests_folder.clone();
+        }
+        if let Some(responses_folder) = &self.responses_folder {
+            config.output.responses_folder = responses_folder.clone();
+        }
+        if let Some(templates) = &self.templates {
+            config.output.templates = Some(templates.clone());
+        }
+        if let Some(tr064_prefix) = &self.tr064_prefix {
+            config.prefixes.tr064 = tr064_prefix.clone();
+        }
+        if let Some(igd_prefix) = &self.igd_prefix {
+            config.prefixes.igd = igd_prefix.clone();
+        }
+        if let Some(include) = &self.include {
+            config.services.include = include.clone();
+        }
+        if let Some(exclude) = &self.exclude {
+            config.services.exclude = exclude.clone();
+        }
+        if let Some(integers) = self.integers {
+            config.types.integers = integers;
+        }
+        if let Some(date_time) = self.date_time {
+            config.types.date_time = date_time;
+        }
+        if let Some(uuid) = self.uuid {
+            config.types.uuid = uuid;
+        }
+        if let Some(binary) = self.binary {
+            config.types.binary = binary;
+        }
+        if let Some(allowed_values) = self.allowed_values {
+            config.types.allowed_values = allowed_values;
+        }
+        if let Some(ranges) = self.ranges {
+            config.types.ranges = ranges;
+        }
+        if self.no_unknown_variant {
+            config.types.unknown_variant = false;
+        }
+        if self.optional_defaults {
+            config.requests.optional_defaults = true;
+        }
+        if self.client {
+            config.client.enabled = true;
+        }
+        if let Some(flavour) = self.client_flavour {
+            config.client.enabled = true;
+            config.client.flavour = flavour;
+        }
+        if self.client_tls {
+            config.client.enabled = true;
+            config.client.tls = true;
+        }
+        config.type_mapping.extend(self.type_mapping.iter().cloned());
+        config.naming.extend(self.naming.iter().cloned());
+        if let Some(access_rights) = &self.access_rights {
+            config.load_access_rights(access_rights)?;
+        }
+
+        Ok(config)
+    }
+}
+
+/// Parses `key=value` pairs.
+fn parse_key_value(input: &str) -> Result<(String, String), String> {
+    match input.split_once('=') {
+        Some((key, value)) => Ok((key.to_string(), value.to_string())),
+        None => Err(format!("expected `key=value`, got `{}`", input)),
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct SnapshotArgs {
+    /// Address of the FRITZ!Box, use an IP if `fritz.box` can't be resolved.
+    #[arg(long, default_value = "http://fritz.box:49000")]
+    pub address: String,
+    /// Description files to mirror.
+    #[arg(long, value_enum, value_delimiter = ',', default_values_t = [Description::Tr064, Description::Igd])]
+    pub descriptions: Vec<Description>,
+    /// Snapshot folder to write into.
+    #[arg(long, default_value = "snapshot")]
+    pub output: PathBuf,
+    #[command(flatten)]
+    pub tls: TlsArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct InspectArgs {
+    #[command(flatten)]
+    pub source: SourceArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    /// Old FRITZ!Box address (`http://...` or `https://...`) or snapshot folder.
+    pub old: String,
+    /// New FRITZ!Box address (`http://...` or `https://...`) or snapshot folder.
+    pub new: String,
+    /// Description files to compare.
+    #[arg(long, value_enum, value_delimiter = ',', default_values_t = [Description::Tr064, Description::Igd])]
+    pub descriptions: Vec<Description>,
+    /// Certificates accepted from the `https://` addresses.
+    #[command(flatten)]
+    pub tls: TlsArgs,
+}
+
+impl DiffArgs {
+    /// Returns the source of the address or snapshot folder `location`, an `https://` address pins the given
+    /// certificates.
+    pub fn source(&self, location: &str) -> Result<DescriptionSource, GeneratorError> {
+        let mut source_config = SourceConfig::default();
+        if location.starts_with("https://") {
+            source_config.address = location.to_string();
+            self.tls.apply(&mut source_config);
+        } else if location.starts_with("http://") {
+            source_config.address = location.to_string();
+        } else {
+            source_config.snapshot = Some(PathBuf::from(location));
+        }
+
+        source_config.source()
+    }
+}
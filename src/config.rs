@@ -0,0 +1,486 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use clap::ValueEnum;
+use crate::api_handling::source::DescriptionSource;
+use crate::error::GeneratorError;
+use crate::tls::Fingerprint;
+
+/// Name of the configuration file read from the current working directory if `--config` isn't given.
+pub const DEFAULT_CONFIG_FILE: &str = "fritzgen.toml";
+
+/// The description files provided by a FRITZ!Box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Description {
+    /// `tr64desc.xml`
+    Tr064,
+    /// `igddesc.xml`
+    Igd,
+}
+
+impl Description {
+    /// Path of the description file on the FRITZ!Box.
+    pub fn file(&self) -> &'static str {
+        match self {
+            Description::Tr064 => "/tr64desc.xml",
+            Description::Igd => "/igddesc.xml",
+        }
+    }
+}
+
+/// Configuration of a generator run, read from `fritzgen.toml`. Every key is optional.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Leave out services whose SCPD can't be fetched, parsed or mapped instead of aborting.
+    pub continue_on_error: bool,
+    pub source: SourceConfig,
+    pub output: OutputConfig,
+    pub prefixes: PrefixConfig,
+    pub services: ServiceFilter,
+    pub types: TypeOptions,
+    pub requests: RequestOptions,
+    pub client: ClientOptions,
+    /// Rust types by UPnP data type, replacing the built-in mapping, e.g. `ui1 = "u8"`.
+    pub type_mapping: BTreeMap<String, String>,
+    /// Rust types of single state variables or arguments, taking precedence over `type_mapping`.
+    #[serde(rename = "type_override")]
+    pub type_overrides: Vec<TypeOverride>,
+    /// Rust names by API name, replacing the result of `rustify_string`, e.g. `NewX_AVM-DE_TAM = "new_tam"`.
+    pub naming: BTreeMap<String, String>,
+    /// Rights needed by the actions, by service type or name and action, see `load_access_rights`.
+    pub access_rights: BTreeMap<String, BTreeMap<String, AccessRight>>,
+}
+
+/// Where the descriptions are read from, `snapshot` takes precedence over `address`.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SourceConfig {
+    pub address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<PathBuf>,
+    pub descriptions: Vec<Description>,
+    /// SHA-256 fingerprints of the certificates accepted from an `https://` address, see `crate::tls`.
+    pub fingerprints: Vec<Fingerprint>,
+    /// PEM file whose certificates are accepted from an `https://` address, next to `fingerprints`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certificate: Option<PathBuf>,
+}
+
+impl Default for SourceConfig {
+    fn default() -> Self {
+        SourceConfig {
+            address: "http://fritz.box:49000".to_string(),
+            snapshot: None,
+            descriptions: vec![Description::Tr064, Description::Igd],
+            fingerprints: vec![],
+            certificate: None,
+        }
+    }
+}
+
+impl SourceConfig {
+    /// Returns the configured source, pinning the certificates of `fingerprints` and `certificate` if any are given.
+    pub fn source(&self) -> Result<DescriptionSource, GeneratorError> {
+        if let Some(folder) = &self.snapshot {
+            return Ok(DescriptionSource::Snapshot(folder.clone()));
+        }
+
+        let address = self.address.trim_end_matches('/').to_string();
+        let mut fingerprints = self.fingerprints.clone();
+        if let Some(path) = &self.certificate {
+            let config_error = |source: std::io::Error| GeneratorError::Config {
+                path: path.clone(),
+                source: source.into(),
+            };
+            let pem = fs::read(path).map_err(config_error)?;
+            fingerprints.extend(Fingerprint::of_pem(&pem).map_err(config_error)?);
+        }
+
+        if fingerprints.is_empty() {
+            Ok(DescriptionSource::Http(address))
+        } else if !address.starts_with("https://") {
+            Err(GeneratorError::Config {
+                path: PathBuf::from(&address),
+                source: "pinned certificates need an `https://` address".into(),
+            })
+        } else {
+            Ok(DescriptionSource::Https { address, fingerprints })
+        }
+    }
+}
+
+/// Layout of the generated files, e.g. `output/tr064_requests`.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputConfig {
+    pub folder: String,
+    pub requests_folder: String,
+    pub responses_folder: String,
+    /// Folder containing the handlebars templates, the built-in templates are used if not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub templates: Option<PathBuf>,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        OutputConfig {
+            folder: "output".to_string(),
+            requests_folder: "requests".to_string(),
+            responses_folder: "responses".to_string(),
+            templates: None,
+        }
+    }
+}
+
+/// Folder and files prefixes of the description files.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PrefixConfig {
+    pub tr064: String,
+    pub igd: String,
+}
+
+impl Default for PrefixConfig {
+    fn default() -> Self {
+        PrefixConfig {
+            tr064: "tr064".to_string(),
+            igd: "igd".to_string(),
+        }
+    }
+}
+
+impl PrefixConfig {
+    /// Folder and files prefix of `description`.
+    pub fn prefix(&self, description: Description) -> &str {
+        match description {
+            Description::Tr064 => &self.tr064,
+            Description::Igd => &self.igd,
+        }
+    }
+}
+
+/// Selects the services to generate files for, either by full service type
+/// (`urn:dslforum-org:service:WLANConfiguration:1`) or by name (`WLANConfiguration`).
+/// An empty `include` list includes every service, `exclude` is applied afterwards.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServiceFilter {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl ServiceFilter {
+    /// Returns whether files are generated for `service_type`.
+    pub fn is_included(&self, service_type: &str) -> bool {
+        let matches = |entry: &String| service_matches(entry, service_type);
+
+        (self.include.is_empty() || self.include.iter().any(matches)) && !self.exclude.iter().any(matches)
+    }
+}
+
+/// Right a FRITZ!Box user needs to call an action, as listed in the access rights tables of AVM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessRight {
+    /// Callable without login.
+    Anonymous,
+    /// Access from FRITZ!Apps.
+    App,
+    /// FRITZ!Box settings.
+    Settings,
+    /// Voice messages, fax messages, FRITZ!App Fon and call list.
+    Phone,
+    /// Access to NAS contents.
+    Nas,
+    /// Smart Home.
+    SmartHome,
+}
+
+impl AccessRight {
+    /// Name of the variant of the generated `AccessRight` enum.
+    pub fn variant(&self) -> &'static str {
+        match self {
+            AccessRight::Anonymous => "Anonymous",
+            AccessRight::App => "App",
+            AccessRight::Settings => "Settings",
+            AccessRight::Phone => "Phone",
+            AccessRight::Nas => "Nas",
+            AccessRight::SmartHome => "SmartHome",
+        }
+    }
+}
+
+/// Returns whether `entry` is the full `service_type` or its name, e.g. `WLANConfiguration`.
+fn service_matches(entry: &str, service_type: &str) -> bool {
+    entry == service_type || service_type.split(':').nth(3) == Some(entry)
+}
+
+/// Replaces the rust type of a state variable, or of a single argument if `action` and `argument` are given instead.
+/// Argument overrides take precedence over state variable overrides.
+///
+/// ```toml
+/// [[type_override]]
+/// service = "Hosts"
+/// action = "GetGenericHostEntry"
+/// argument = "NewMACAddress"
+/// type = "crate::MacAddress"
+/// deserialize_with = "crate::mac::deserialize"
+/// format_with = "crate::mac::format"
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TypeOverride {
+    /// Service type or name.
+    pub service: String,
+    /// Name of the state variable, every argument related to it gets `type_name`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variable: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub argument: Option<String>,
+    /// Path of the rust type, it has to implement `Deserialize` and `Display` unless the functions below are given.
+    #[serde(rename = "type")]
+    pub type_name: String,
+    /// Path of a serde `deserialize_with` function used for response fields.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deserialize_with: Option<String>,
+    /// Path of a function taking `&type` and returning the `Display` value put into requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format_with: Option<String>,
+}
+
+impl TypeOverride {
+    /// Checks that the override selects a state variable, or an argument by `action` and `argument`, as an override
+    /// without either would replace every argument of the service.
+    pub fn check(&self) -> Result<(), &'static str> {
+        match (&self.variable, &self.action, &self.argument) {
+            (Some(_), None, None) | (None, Some(_), Some(_)) => Ok(()),
+            (None, None, None) => Err("a type override needs `variable`, or `action` and `argument`"),
+            (None, Some(_), None) | (None, None, Some(_)) => {
+                Err("a type override of an argument needs both `action` and `argument`")
+            }
+            (Some(_), _, _) => Err("a type override takes either `variable`, or `action` and `argument`"),
+        }
+    }
+
+    /// Returns whether the override applies to `argument` of `action`, which is related to `variable`.
+    pub fn matches(&self, service_type: &str, action: &str, argument: &str, variable: &str) -> bool {
+        let matches = |entry: &Option<String>, name: &str| entry.as_deref().is_none_or(|entry| entry == name);
+
+        service_matches(&self.service, service_type)
+            && matches(&self.variable, variable)
+            && matches(&self.action, action)
+            && matches(&self.argument, argument)
+    }
+}
+
+/// Selects how UPnP data types are mapped to rust types, `type_mapping` takes precedence.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TypeOptions {
+    pub integers: IntegerMapping,
+    pub date_time: DateTimeMapping,
+    pub uuid: UuidMapping,
+    pub binary: BinaryMapping,
+    pub allowed_values: AllowedValuesMapping,
+    pub ranges: RangeMapping,
+    /// Adds an `Unknown(String)` variant to the generated enums, catching values the description doesn't list.
+    pub unknown_variant: bool,
+}
+
+impl Default for TypeOptions {
+    fn default() -> Self {
+        TypeOptions {
+            integers: IntegerMapping::default(),
+            date_time: DateTimeMapping::default(),
+            uuid: UuidMapping::default(),
+            binary: BinaryMapping::default(),
+            allowed_values: AllowedValuesMapping::default(),
+            ranges: RangeMapping::default(),
+            unknown_variant: true,
+        }
+    }
+}
+
+/// Rust types of the UPnP integer types `ui1`, `ui2`, `i1` and `i2`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IntegerMapping {
+    /// `u32` and `i32`, compatible with files generated by earlier versions.
+    #[default]
+    Widened,
+    /// `u8`, `u16`, `i8` and `i16`, matching the range the FRITZ!Box accepts.
+    Exact,
+}
+
+/// Rust types of the UPnP types `date`, `dateTime` and `dateTime.tz`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DateTimeMapping {
+    /// `String`, the value as sent by the FRITZ!Box.
+    #[default]
+    String,
+    /// `chrono::NaiveDate`, `chrono::NaiveDateTime` and `chrono::DateTime<chrono::FixedOffset>`,
+    /// the generated files depend on `chrono` with the `serde` feature.
+    Chrono,
+}
+
+/// Rust type of the UPnP type `uuid`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UuidMapping {
+    /// `String`, the value as sent by the FRITZ!Box.
+    #[default]
+    String,
+    /// `uuid::Uuid`, the generated files depend on `uuid` with the `serde` feature.
+    Uuid,
+}
+
+/// Rust type of the UPnP types `bin.base64` and `bin.hex`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BinaryMapping {
+    /// `String`, the encoded value as sent by the FRITZ!Box.
+    #[default]
+    String,
+    /// `Vec<u8>`, decoded and encoded by the generated files, which depend on `base64` and `hex`.
+    Bytes,
+}
+
+/// Rust type of `string` state variables with an `allowedValueList`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AllowedValuesMapping {
+    /// `String`, like every other `string` state variable.
+    #[default]
+    String,
+    /// An enum per state variable, generated into the response file of the service.
+    Enum,
+}
+
+/// Shape of the generated request functions.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RequestOptions {
+    /// Takes arguments whose state variable has a `defaultValue` as `Option`, `None` sends the default.
+    pub optional_defaults: bool,
+}
+
+/// The generated client calling the actions over HTTP.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClientOptions {
+    /// Generates `client.rs` with the clients of `flavour` and a method per action in the request files.
+    pub enabled: bool,
+    pub flavour: ClientFlavour,
+    /// Adds `with_pinned_certificates` to the clients for HTTPS with the certificate of the FRITZ!Box, which needs
+    /// `rustls`, `rustls-pemfile` and `sha2` as dependencies.
+    pub tls: bool,
+}
+
+impl ClientOptions {
+    /// Whether the blocking `FritzClient` is generated.
+    pub fn blocking(&self) -> bool {
+        self.enabled && matches!(self.flavour, ClientFlavour::Blocking | ClientFlavour::Both)
+    }
+
+    /// Whether the `AsyncFritzClient` is generated.
+    pub fn asynchronous(&self) -> bool {
+        self.enabled && matches!(self.flavour, ClientFlavour::Async | ClientFlavour::Both)
+    }
+}
+
+/// The clients generated if `ClientOptions::enabled` is set.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClientFlavour {
+    /// `FritzClient`, sending with `reqwest::blocking`.
+    #[default]
+    Blocking,
+    /// `AsyncFritzClient`, with an `async fn` per action.
+    Async,
+    /// Both clients, sharing the request and response types.
+    Both,
+}
+
+/// Rust type of integer state variables with an `allowedValueRange`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RangeMapping {
+    /// The integer type, like every other integer state variable.
+    #[default]
+    Plain,
+    /// A newtype per state variable with a checked constructor and the range as associated constants,
+    /// generated into the response file of the service.
+    Checked,
+}
+
+impl Config {
+    /// Reads the configuration from `path`. If `path` is `None`, `fritzgen.toml` is read if present,
+    /// otherwise the defaults are used.
+    pub fn load(path: Option<&Path>) -> Result<Self, GeneratorError> {
+        let path = match path {
+            Some(path) => path,
+            None if Path::new(DEFAULT_CONFIG_FILE).exists() => Path::new(DEFAULT_CONFIG_FILE),
+            None => return Ok(Config::default()),
+        };
+        let config_error = |source: Box<dyn std::error::Error + Send + Sync>| GeneratorError::Config {
+            path: path.to_path_buf(),
+            source,
+        };
+        let content = fs::read_to_string(path).map_err(|e| config_error(e.into()))?;
+        let config: Config = toml::from_str(&content).map_err(|e| config_error(e.into()))?;
+
+        config.check(path)?;
+        Ok(config)
+    }
+
+    /// Checks the parts serde can't, `path` is reported in the error.
+    pub fn check(&self, path: &Path) -> Result<(), GeneratorError> {
+        for type_override in &self.type_overrides {
+            type_override.check().map_err(|source| GeneratorError::Config {
+                path: path.to_path_buf(),
+                source: format!("{} (service {})", source, type_override.service).into(),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Adds the access rights of the TOML file at `path`, laid out like `[access_rights]`:
+    ///
+    /// ```toml
+    /// [DeviceConfig]
+    /// Reboot = "settings"
+    /// X_AVM-DE_CreateUrlSID = "app"
+    /// ```
+    pub fn load_access_rights(&mut self, path: &Path) -> Result<(), GeneratorError> {
+        let config_error = |source: Box<dyn std::error::Error + Send + Sync>| GeneratorError::Config {
+            path: path.to_path_buf(),
+            source,
+        };
+        let content = fs::read_to_string(path).map_err(|e| config_error(e.into()))?;
+        let access_rights: BTreeMap<String, BTreeMap<String, AccessRight>> =
+            toml::from_str(&content).map_err(|e| config_error(e.into()))?;
+
+        for (service, actions) in access_rights {
+            self.access_rights.entry(service).or_default().extend(actions);
+        }
+        Ok(())
+    }
+
+    /// Returns the right needed to call `action` of `service_type`, `None` if the access rights don't list it.
+    /// An entry by service type takes precedence over one by service name.
+    pub fn access_right(&self, service_type: &str, action: &str) -> Option<AccessRight> {
+        let listed = |service: &str| self.access_rights.get(service)?.get(action).copied();
+
+        listed(service_type).or_else(|| listed(service_type.split(':').nth(3)?))
+    }
+
+    /// Returns the resolved configuration as TOML.
+    pub fn explain(&self) -> String {
+        toml::to_string_pretty(self).expect("the configuration only contains TOML compatible types")
+    }
+}
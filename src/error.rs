@@ -0,0 +1,105 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+/// Everything that can go wrong while generating files.
+#[derive(Debug)]
+pub enum GeneratorError {
+    /// A description couldn't be fetched from `location`, a URL or a file inside the snapshot folder.
+    Fetch {
+        location: String,
+        source: Box<dyn Error + Send + Sync>,
+    },
+    /// The description at `location` isn't valid XML or doesn't match the expected format.
+    Parse {
+        location: String,
+        source: serde_xml_rs::Error,
+    },
+    /// The related state variable of an argument has a data type without a rust mapping.
+    UnknownDataType {
+        service: String,
+        action: String,
+        variable: String,
+        data_type: String,
+    },
+    /// An argument refers to a state variable the service state table doesn't contain.
+    MissingStateVariable {
+        service: String,
+        action: String,
+        argument: String,
+        variable: String,
+    },
+    /// A service couldn't be handled, `source` tells why.
+    Service {
+        service_type: String,
+        scpd_url: String,
+        source: Box<GeneratorError>,
+    },
+    /// A template couldn't be registered.
+    Template(handlebars::TemplateError),
+    /// A template couldn't be rendered.
+    TemplateRender {
+        template: String,
+        source: handlebars::RenderError,
+    },
+    /// The configuration file couldn't be read or parsed.
+    Config {
+        path: PathBuf,
+        source: Box<dyn Error + Send + Sync>,
+    },
+    /// A file couldn't be written.
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+}
+
+impl Display for GeneratorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GeneratorError::Fetch { location, source } => write!(f, "failed to fetch {}: {}", location, source),
+            GeneratorError::Parse { location, source } => write!(f, "failed to parse {}: {}", location, source),
+            GeneratorError::UnknownDataType { service, action, variable, data_type } => write!(
+                f,
+                "unknown data type `{}` of state variable {} used by {} of {}, please open a ticket",
+                data_type, variable, action, service
+            ),
+            GeneratorError::MissingStateVariable { service, action, argument, variable } => write!(
+                f,
+                "state variable {} of argument {} of {} is missing in {}",
+                variable, argument, action, service
+            ),
+            GeneratorError::Service { service_type, scpd_url, source } => {
+                write!(f, "service {} ({}): {}", service_type, scpd_url, source)
+            }
+            GeneratorError::Template(source) => write!(f, "failed to register template: {}", source),
+            GeneratorError::TemplateRender { template, source } => {
+                write!(f, "failed to render template {}: {}", template, source)
+            }
+            GeneratorError::Config { path, source } => write!(f, "failed to read {}: {}", path.display(), source),
+            GeneratorError::Io { path, source } => write!(f, "failed to write {}: {}", path.display(), source),
+        }
+    }
+}
+
+impl Error for GeneratorError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GeneratorError::Fetch { source, .. } => Some(source.as_ref()),
+            GeneratorError::Parse { source, .. } => Some(source),
+            GeneratorError::Service { source, .. } => Some(source.as_ref()),
+            GeneratorError::Template(source) => Some(source),
+            GeneratorError::TemplateRender { source, .. } => Some(source),
+            GeneratorError::Config { source, .. } => Some(source.as_ref()),
+            GeneratorError::Io { source, .. } => Some(source),
+            GeneratorError::UnknownDataType { .. } | GeneratorError::MissingStateVariable { .. } => None,
+        }
+    }
+}
+
+impl From<handlebars::TemplateError> for GeneratorError {
+    fn from(error: handlebars::TemplateError) -> Self {
+        GeneratorError::Template(error)
+    }
+}
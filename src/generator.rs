@@ -0,0 +1,372 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use handlebars::{DirectorySourceOptions, Handlebars, handlebars_helper};
+use serde::Serialize;
+use crate::api_handling::api_desc_dir::ApiDescDir;
+use crate::api_handling::source::DescriptionSource;
+use crate::config::{
+    AccessRight, AllowedValuesMapping, BinaryMapping, ClientFlavour, Config, DateTimeMapping, Description, IntegerMapping, RangeMapping,
+    TypeOverride, UuidMapping,
+};
+use crate::error::GeneratorError;
+use crate::sink::{FolderSink, MemorySink, OutputSink};
+
+/// Templates compiled into the generator, used if no template folder is configured.
+const BUILTIN_TEMPLATES: [(&str, &str); 7] = [
+    ("action_response_types", include_str!("../templates/action_response_types.rs")),
+    ("client", include_str!("../templates/client.rs")),
+    ("mod", include_str!("../templates/mod.rs")),
+    ("multi_use", include_str!("../templates/multi_use.rs")),
+    ("request_function", include_str!("../templates/request_function.rs")),
+    ("request_multi_use", include_str!("../templates/request_multi_use.rs")),
+    ("soap", include_str!("../templates/soap.rs")),
+];
+
+/// File shared by all descriptions, next to the requests and responses folders.
+pub const SOAP_FILE_NAME: &str = "soap.rs";
+
+/// File of the `FritzClient`, next to `SOAP_FILE_NAME`.
+pub const CLIENT_FILE_NAME: &str = "client.rs";
+
+/// Outcome of a generator run.
+#[derive(Debug, Default)]
+pub struct GenerationResult {
+    /// Generated files, relative to the output root.
+    pub files: Vec<PathBuf>,
+    /// Problems that didn't stop the generation.
+    pub warnings: Vec<String>,
+    /// Locations of all descriptions read, URLs or files inside the snapshot folder.
+    pub inputs: Vec<String>,
+    /// Services left out because of `Config::continue_on_error`.
+    pub skipped_services: Vec<SkippedService>,
+}
+
+/// A service no files were generated for, see `Config::continue_on_error`.
+#[derive(Debug)]
+pub struct SkippedService {
+    pub service_type: String,
+    pub scpd_url: String,
+    pub error: GeneratorError,
+}
+
+/// Generates request and response files, configured builder style.
+///
+/// ```no_run
+/// use fritz_box_tr064_igd_api_files_generator::{Generator, MemorySink};
+/// use fritz_box_tr064_igd_api_files_generator::api_handling::source::DescriptionSource;
+///
+/// let mut sink = MemorySink::default();
+/// let result = Generator::new()
+///     .source(DescriptionSource::Snapshot("snapshots/7.57".into()))
+///     .exclude_service("X_AVM-DE_Dect")
+///     .sink(&mut sink)
+///     .generate()?;
+/// println!("generated {} files", result.files.len());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct Generator<'s> {
+    config: Config,
+    source: Option<DescriptionSource>,
+    sink: Option<Box<dyn OutputSink + 's>>,
+}
+
+impl Default for Generator<'_> {
+    fn default() -> Self {
+        Generator::new()
+    }
+}
+
+impl<'s> Generator<'s> {
+    /// Creates a generator with the default configuration.
+    pub fn new() -> Self {
+        Generator::from_config(Config::default())
+    }
+
+    /// Creates a generator with `config`, e.g. read from `fritzgen.toml`.
+    pub fn from_config(config: Config) -> Self {
+        Generator {
+            config,
+            source: None,
+            sink: None,
+        }
+    }
+
+    /// Reads the descriptions from `source` instead of the configured `[source]`.
+    pub fn source(mut self, source: DescriptionSource) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Uses the templates in `folder` instead of the built-in templates.
+    pub fn templates(mut self, folder: impl Into<PathBuf>) -> Self {
+        self.config.output.templates = Some(folder.into());
+        self
+    }
+
+    /// Writes the files into `sink` instead of the configured output folder.
+    pub fn sink(mut self, sink: impl OutputSink + 's) -> Self {
+        self.sink = Some(Box::new(sink));
+        self
+    }
+
+    /// Only processes `descriptions`.
+    pub fn descriptions(mut self, descriptions: &[Description]) -> Self {
+        self.config.source.descriptions = descriptions.to_vec();
+        self
+    }
+
+    /// Sets the folder and files prefix of `description`.
+    pub fn prefix(mut self, description: Description, prefix: &str) -> Self {
+        match description {
+            Description::Tr064 => self.config.prefixes.tr064 = prefix.to_string(),
+            Description::Igd => self.config.prefixes.igd = prefix.to_string(),
+        }
+        self
+    }
+
+    /// Only generates files for the included services, by service type or name.
+    pub fn include_service(mut self, service: &str) -> Self {
+        self.config.services.include.push(service.to_string());
+        self
+    }
+
+    /// Doesn't generate files for `service`, by service type or name.
+    pub fn exclude_service(mut self, service: &str) -> Self {
+        self.config.services.exclude.push(service.to_string());
+        self
+    }
+
+    /// Leaves out failing services instead of aborting, they are listed in `GenerationResult::skipped_services`.
+    pub fn continue_on_error(mut self, continue_on_error: bool) -> Self {
+        self.config.continue_on_error = continue_on_error;
+        self
+    }
+
+    /// Selects the rust types of `ui1`, `ui2`, `i1` and `i2`.
+    pub fn integers(mut self, integers: IntegerMapping) -> Self {
+        self.config.types.integers = integers;
+        self
+    }
+
+    /// Selects the rust types of `date`, `dateTime` and `dateTime.tz`.
+    pub fn date_time(mut self, date_time: DateTimeMapping) -> Self {
+        self.config.types.date_time = date_time;
+        self
+    }
+
+    /// Selects the rust type of `uuid`.
+    pub fn uuid(mut self, uuid: UuidMapping) -> Self {
+        self.config.types.uuid = uuid;
+        self
+    }
+
+    /// Selects the rust type of `bin.base64` and `bin.hex`.
+    pub fn binary(mut self, binary: BinaryMapping) -> Self {
+        self.config.types.binary = binary;
+        self
+    }
+
+    /// Selects the rust type of `string` state variables with allowed values, with or without an `Unknown(String)` variant.
+    pub fn allowed_values(mut self, allowed_values: AllowedValuesMapping, unknown_variant: bool) -> Self {
+        self.config.types.allowed_values = allowed_values;
+        self.config.types.unknown_variant = unknown_variant;
+        self
+    }
+
+    /// Selects the rust type of integer state variables with an allowed range.
+    pub fn ranges(mut self, ranges: RangeMapping) -> Self {
+        self.config.types.ranges = ranges;
+        self
+    }
+
+    /// Takes arguments with a default value as `Option` in the request functions, `None` sends the default.
+    pub fn optional_defaults(mut self, optional_defaults: bool) -> Self {
+        self.config.requests.optional_defaults = optional_defaults;
+        self
+    }
+
+    /// Generates a `FritzClient` with a method per action.
+    pub fn client(mut self, enabled: bool) -> Self {
+        self.config.client.enabled = enabled;
+        self
+    }
+
+    /// Generates the blocking, the async or both clients, implies `client(true)`.
+    pub fn client_flavour(mut self, flavour: ClientFlavour) -> Self {
+        self.config.client.enabled = true;
+        self.config.client.flavour = flavour;
+        self
+    }
+
+    /// Lets the clients pin the certificate of the FRITZ!Box for HTTPS, implies `client(true)`.
+    pub fn client_tls(mut self, enabled: bool) -> Self {
+        self.config.client.enabled |= enabled;
+        self.config.client.tls = enabled;
+        self
+    }
+
+    /// Maps the UPnP `data_type` to `rust_type`.
+    pub fn type_mapping(mut self, data_type: &str, rust_type: &str) -> Self {
+        self.config.type_mapping.insert(data_type.to_string(), rust_type.to_string());
+        self
+    }
+
+    /// Replaces the rust type of a state variable or argument, see `TypeOverride`.
+    /// `generate` fails with `GeneratorError::Config` if the override selects neither.
+    pub fn type_override(mut self, type_override: TypeOverride) -> Self {
+        self.config.type_overrides.push(type_override);
+        self
+    }
+
+    /// Records that `action` of `service`, by service type or name, needs `right`.
+    pub fn access_right(mut self, service: &str, action: &str, right: AccessRight) -> Self {
+        self.config
+            .access_rights
+            .entry(service.to_string())
+            .or_default()
+            .insert(action.to_string(), right);
+        self
+    }
+
+    /// Uses `rust_name` for the API name `name`.
+    pub fn naming(mut self, name: &str, rust_name: &str) -> Self {
+        self.config.naming.insert(name.to_string(), rust_name.to_string());
+        self
+    }
+
+    /// The configuration the files will be generated with.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Reads all configured descriptions and writes the generated files.
+    pub fn generate(self) -> Result<GenerationResult, GeneratorError> {
+        self.config.check(Path::new("[[type_override]]"))?;
+        let handlebars = self.handlebars()?;
+        let Generator { config, source, sink } = self;
+        let source = match source {
+            Some(source) => source,
+            None => config.source.source()?,
+        };
+        let mut sink = sink.unwrap_or_else(|| Box::new(FolderSink::new(&config.output.folder)));
+        let mut result = GenerationResult::default();
+
+        for description in &config.source.descriptions {
+            result.inputs.push(source.location(description.file()));
+            let api_desc_dir: ApiDescDir = source.fetch_description(description.file())?;
+            let description_result = api_desc_dir.generate_files(
+                &source,
+                Some(config.prefixes.prefix(*description).to_string()),
+                &config,
+                &handlebars,
+                sink.as_mut(),
+            )?;
+            result.files.extend(description_result.files);
+            result.warnings.extend(description_result.warnings);
+            result.inputs.extend(description_result.inputs);
+            result.skipped_services.extend(description_result.skipped_services);
+        }
+
+        // template folders written before the `soap` template don't need the file
+        if handlebars.has_template("soap") {
+            result.files.push(write_shared_file(&handlebars, sink.as_mut(), "soap", SOAP_FILE_NAME, &())?);
+        }
+        if config.client.enabled && handlebars.has_template("client") {
+            let data = serde_json::json!({
+                "blocking": config.client.blocking(),
+                "async": config.client.asynchronous(),
+                "tls": config.client.tls,
+            });
+            result.files.push(write_shared_file(&handlebars, sink.as_mut(), "client", CLIENT_FILE_NAME, &data)?);
+        }
+
+        Ok(result)
+    }
+
+    /// Generates all files into the single root module at `path` instead of a sink, see `MemorySink::root_module`.
+    /// Meant for build scripts, prints `cargo:rerun-if-changed` for the templates and every description file read
+    /// from a snapshot.
+    pub fn generate_root_module(self, path: impl AsRef<Path>) -> Result<GenerationResult, GeneratorError> {
+        let Generator { config, source, .. } = self;
+        let source = match source {
+            Some(source) => source,
+            None => config.source.source()?,
+        };
+        let from_snapshot = matches!(source, DescriptionSource::Snapshot(_));
+        if let Some(template_folder) = &config.output.templates {
+            println!("cargo:rerun-if-changed={}", template_folder.display());
+        }
+
+        let mut sink = MemorySink::default();
+        let result = Generator {
+            config,
+            source: Some(source),
+            sink: Some(Box::new(&mut sink)),
+        }
+        .generate()?;
+
+        if from_snapshot {
+            for input in &result.inputs {
+                println!("cargo:rerun-if-changed={}", input);
+            }
+        }
+        fs::write(path.as_ref(), sink.root_module()).map_err(|source| GeneratorError::Io {
+            path: path.as_ref().to_path_buf(),
+            source,
+        })?;
+
+        Ok(result)
+    }
+
+    /// Registers the configured or built-in templates.
+    fn handlebars(&self) -> Result<Handlebars<'static>, GeneratorError> {
+        let mut handlebars = Handlebars::new();
+        // `string_to_string_slice` is kept for template folders written before `borrowed_type`
+        handlebars_helper!(string_to_string_slice: |value: String| if value == "String" {"&str".to_string()} else {value});
+        handlebars.register_helper("string_to_string_slice", Box::new(string_to_string_slice));
+        handlebars_helper!(borrowed_type: |value: String| match value.as_str() {
+            "String" => "&str".to_string(),
+            "Vec<u8>" => "&[u8]".to_string(),
+            _ => value,
+        });
+        handlebars.register_helper("borrowed_type", Box::new(borrowed_type));
+
+        match &self.config.output.templates {
+            Some(template_folder) => {
+                let directory_source_options = DirectorySourceOptions{
+                    tpl_extension: ".rs".to_string(),
+                    hidden: false,
+                    temporary: false,
+                };
+                handlebars.register_templates_directory(template_folder, directory_source_options)?;
+            }
+            None => {
+                for (name, template) in BUILTIN_TEMPLATES {
+                    handlebars.register_template_string(name, template)?;
+                }
+            }
+        }
+
+        Ok(handlebars)
+    }
+}
+
+/// Renders `template` with `data` into the file `file_name` next to the requests and responses folders.
+fn write_shared_file<T: Serialize>(
+    handlebars: &Handlebars,
+    sink: &mut dyn OutputSink,
+    template: &str,
+    file_name: &str,
+    data: &T,
+) -> Result<PathBuf, GeneratorError> {
+    let path = PathBuf::from(file_name);
+    let content = handlebars.render(template, data).map_err(|source| GeneratorError::TemplateRender {
+        template: template.to_string(),
+        source,
+    })?;
+    sink.write_file(&path, &content).map_err(|source| GeneratorError::Io {
+        path: path.clone(),
+        source,
+    })?;
+    Ok(path)
+}
@@ -0,0 +1,19 @@
+//! Generates rust source code files for the AVM FRITZ!Box TR-064 and IGD APIs.
+//! The `Generator` builder is the entry point, the binary is a thin wrapper around it.
+
+#[macro_use]
+extern crate serde;
+extern crate serde_xml_rs;
+
+pub mod api_handling;
+pub mod build_script;
+pub mod config;
+pub mod error;
+pub mod generator;
+pub mod sink;
+pub mod tls;
+
+pub use crate::config::{Config, Description};
+pub use crate::error::GeneratorError;
+pub use crate::generator::{GenerationResult, Generator};
+pub use crate::sink::{FolderSink, MemorySink, OutputSink};
@@ -1,54 +1,115 @@
-#[macro_use]
-extern crate serde;
-extern crate serde_xml_rs;
-
-mod api_handling;
-
-use handlebars::{DirectorySourceOptions, Handlebars, handlebars_helper};
-use crate::api_handling::api_desc_dir::ApiDescDir;
-
-/// If you can't reach your target or wish to specify it via IP, this is the place.
-const ADDRESS: &str = "http://10.0.0.254:49000";
-/// Specify the requests output folder.
-const REQUESTS_OUTPUT_FOLDER: &str = "requests";
-/// Specify the responses output folder.
-const RESPONSES_OUTPUT_FOLDER: &str = "responses";
-/// Specify the TR-064 folder and files prefix
-const TR064_PREFIX: &str = "tr064";
-/// Specify the IGD folder and files prefix
-const IGD_PREFIX: &str = "igd";
-
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-
-    let mut handlebars = Handlebars::new();
-    handlebars_helper!(string_to_string_slice: |value: String| if value == "String" {"&str".to_string()} else {value});
-    handlebars.register_helper("string_to_string_slice", Box::new(string_to_string_slice));
-
-    let directory_source_options = DirectorySourceOptions{
-        tpl_extension: ".rs".to_string(),
-        hidden: false,
-        temporary: false,
+mod cli;
+
+use std::process::ExitCode;
+use clap::Parser;
+use fritz_box_tr064_igd_api_files_generator::{Generator, GeneratorError};
+use fritz_box_tr064_igd_api_files_generator::api_handling::inspect::{diff, summarize};
+use fritz_box_tr064_igd_api_files_generator::api_handling::snapshot::take_snapshot;
+use fritz_box_tr064_igd_api_files_generator::config::SourceConfig;
+use crate::cli::{Cli, Command, DiffArgs, GenerateArgs, InspectArgs, SnapshotArgs};
+
+fn main() -> ExitCode {
+    let result = match Cli::parse().command {
+        Command::Generate(args) => generate(*args),
+        Command::Snapshot(args) => snapshot(args),
+        Command::Inspect(args) => inspect(args),
+        Command::Diff(args) => compare(args),
     };
-    handlebars.register_templates_directory("templates", directory_source_options).expect("template directory");
-
-    let resp = reqwest::blocking::get(format!("{}/tr64desc.xml", ADDRESS).as_str())?.text()?;
-    let tr64desc: ApiDescDir = serde_xml_rs::from_str(&resp)?;
-    tr64desc.generate_files(
-        ADDRESS,
-        RESPONSES_OUTPUT_FOLDER.to_string(),
-        REQUESTS_OUTPUT_FOLDER.to_string(),
-        Some(TR064_PREFIX.to_string()),
-        &handlebars,
-    );
-    let resp = reqwest::blocking::get(format!("{}/igddesc.xml", ADDRESS).as_str())?.text()?;
-    let igddesc: ApiDescDir = serde_xml_rs::from_str(&resp)?;
-    igddesc.generate_files(
-        ADDRESS,
-        RESPONSES_OUTPUT_FOLDER.to_string(),
-        REQUESTS_OUTPUT_FOLDER.to_string(),
-        Some(IGD_PREFIX.to_string()),
-        &handlebars
+
+    result.unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        ExitCode::FAILURE
+    })
+}
+
+fn generate(args: GenerateArgs) -> Result<ExitCode, GeneratorError> {
+    let config = args.config()?;
+    if args.explain {
+        print!("{}", config.explain());
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let output_folder = config.output.folder.clone();
+    let result = Generator::from_config(config).generate()?;
+    for warning in &result.warnings {
+        eprintln!("warning: {}", warning);
+    }
+    println!("Generated {} files into {}", result.files.len(), output_folder);
+
+    if result.skipped_services.is_empty() {
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let mut rows = vec![[String::from("skipped service"), String::from("SCPD"), String::from("reason")]];
+    for skipped in &result.skipped_services {
+        rows.push([skipped.service_type.clone(), skipped.scpd_url.clone(), skipped.error.to_string()]);
+    }
+    let service_width = rows.iter().map(|row| row[0].len()).max().unwrap_or_default();
+    let scpd_width = rows.iter().map(|row| row[1].len()).max().unwrap_or_default();
+    for row in &rows {
+        eprintln!("{:service_width$}  {:scpd_width$}  {}", row[0], row[1], row[2]);
+    }
+
+    Ok(ExitCode::from(2))
+}
+
+fn snapshot(args: SnapshotArgs) -> Result<ExitCode, GeneratorError> {
+    let mut source_config = SourceConfig {
+        address: args.address.clone(),
+        ..SourceConfig::default()
+    };
+    args.tls.apply(&mut source_config);
+    let source = source_config.source()?;
+    let description_files: Vec<&str> = args.descriptions.iter().map(|description| description.file()).collect();
+    let manifest = take_snapshot(&source, &description_files, &args.output)?;
+    println!(
+        "Mirrored {} files of {} ({}) into {}",
+        manifest.files.len(),
+        manifest.model_name,
+        manifest.system_version.display,
+        args.output.display()
     );
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
+}
+
+fn inspect(args: InspectArgs) -> Result<ExitCode, GeneratorError> {
+    let config = args.source.config()?;
+    let source = config.source.source()?;
+
+    for description in &config.source.descriptions {
+        let summary = summarize(&source, description.file())?;
+        println!("{}: {} ({})", description.file(), summary.model_name, summary.firmware);
+        for (key, service) in &summary.services {
+            println!("  {} ({}, {})", key.service_type, key.control_url, service.scpd_url);
+            for (name, action) in &service.actions {
+                println!("    {}{}", name, action);
+            }
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn compare(args: DiffArgs) -> Result<ExitCode, GeneratorError> {
+    let old_source = args.source(&args.old)?;
+    let new_source = args.source(&args.new)?;
+    let mut differs = false;
+
+    for description in &args.descriptions {
+        let old = summarize(&old_source, description.file())?;
+        let new = summarize(&new_source, description.file())?;
+        let lines = diff(&old, &new);
+        if lines.is_empty() {
+            continue;
+        }
+
+        differs = true;
+        println!("{}: {} ({}) -> {} ({})", description.file(), old.model_name, old.firmware, new.model_name, new.firmware);
+        for line in lines {
+            println!("  {}", line);
+        }
+    }
+
+    Ok(if differs { ExitCode::FAILURE } else { ExitCode::SUCCESS })
 }
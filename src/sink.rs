@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Receives the generated files. Paths are relative to the output root, e.g. `tr064_requests/mod.rs`.
+pub trait OutputSink {
+    /// Stores `content` as the file at `path`, replacing a previously stored file.
+    fn write_file(&mut self, path: &Path, content: &str) -> io::Result<()>;
+}
+
+/// Writes the generated files into a folder, creating missing folders on the way.
+#[derive(Debug, Clone)]
+pub struct FolderSink {
+    pub root: PathBuf,
+}
+
+impl FolderSink {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        FolderSink { root: root.into() }
+    }
+}
+
+impl OutputSink for FolderSink {
+    fn write_file(&mut self, path: &Path, content: &str) -> io::Result<()> {
+        let path = self.root.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)
+    }
+}
+
+/// Keeps the generated files in memory, e.g. to compare them with files on disk.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySink {
+    pub files: BTreeMap<PathBuf, String>,
+}
+
+impl MemorySink {
+    /// Combines all files into a single root module, each folder and file becoming an inline module,
+    /// e.g. `tr064_requests/tr064_device_config.rs` becomes `pub mod tr064_requests { pub mod tr064_device_config { .. } }`.
+    /// The `mod.rs` files are left out, the inline modules replace their module declarations.
+    pub fn root_module(&self) -> String {
+        let mut content = String::new();
+        let mut open_modules: Vec<String> = vec![];
+
+        for (path, file_content) in &self.files {
+            if path.file_name().is_some_and(|file_name| file_name == "mod.rs") {
+                continue;
+            }
+            let mut modules: Vec<String> = path
+                .with_extension("")
+                .iter()
+                .map(|component| component.to_string_lossy().to_string())
+                .collect();
+            let file_module = modules.pop().unwrap_or_default();
+
+            let common_modules = open_modules.iter().zip(&modules).take_while(|(open, module)| open == module).count();
+            for _ in common_modules..open_modules.len() {
+                content.push_str("}\n");
+            }
+            open_modules.truncate(common_modules);
+            for module in &modules[common_modules..] {
+                content.push_str(&format!("pub mod {} {{\n", module));
+                open_modules.push(module.clone());
+            }
+
+            // the file content isn't indented, it contains raw strings
+            content.push_str(&format!("pub mod {} {{\n{}\n}}\n", file_module, file_content));
+        }
+        for _ in open_modules {
+            content.push_str("}\n");
+        }
+
+        content
+    }
+}
+
+impl OutputSink for MemorySink {
+    fn write_file(&mut self, path: &Path, content: &str) -> io::Result<()> {
+        self.files.insert(path.to_path_buf(), content.to_string());
+        Ok(())
+    }
+}
+
+impl<T: OutputSink + ?Sized> OutputSink for &mut T {
+    fn write_file(&mut self, path: &Path, content: &str) -> io::Result<()> {
+        (**self).write_file(path, content)
+    }
+}
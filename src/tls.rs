@@ -0,0 +1,147 @@
+//! HTTPS with the self-signed certificate of a FRITZ!Box. It serves TR-064 over HTTPS on the port returned by
+//! `GetSecurityPort` of `DeviceInfo`, usually `https://fritz.box:49443`, with a certificate no root signed. Instead of
+//! verifying a chain and the host name, the certificate itself is pinned by its SHA-256 fingerprint.
+
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::str::FromStr;
+use std::sync::Arc;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, WebPkiSupportedAlgorithms};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{CertificateError, DigitallySignedStruct, SignatureScheme};
+use sha2::{Digest, Sha256};
+
+/// SHA-256 fingerprint of a certificate, written as colon separated hex like `openssl x509 -fingerprint -sha256`
+/// prints it. Parsing also accepts the hex digits without colons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Fingerprint([u8; 32]);
+
+impl Fingerprint {
+    /// Fingerprint of the DER encoded `certificate`.
+    pub fn of(certificate: &[u8]) -> Self {
+        Fingerprint(Sha256::digest(certificate).into())
+    }
+
+    /// Fingerprints of the certificates in the PEM file content `pem`, e.g. the certificate exported from the
+    /// FRITZ!Box. Fails if it doesn't contain any.
+    pub fn of_pem(mut pem: &[u8]) -> io::Result<Vec<Fingerprint>> {
+        let fingerprints = rustls_pemfile::certs(&mut pem)
+            .map(|certificate| certificate.map(|certificate| Fingerprint::of(&certificate)))
+            .collect::<io::Result<Vec<Fingerprint>>>()?;
+        if fingerprints.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "no certificate found"));
+        }
+
+        Ok(fingerprints)
+    }
+}
+
+impl FromStr for Fingerprint {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let digits: Vec<u8> = input.bytes().filter(|digit| *digit != b':').collect();
+        let mut fingerprint = [0; 32];
+        if digits.len() != fingerprint.len() * 2 {
+            return Err(format!("expected a SHA-256 fingerprint of 64 hex digits, got `{}`", input));
+        }
+        for (byte, pair) in fingerprint.iter_mut().zip(digits.chunks(2)) {
+            let pair = std::str::from_utf8(pair).map_err(|e| e.to_string())?;
+            *byte = u8::from_str_radix(pair, 16).map_err(|_| format!("invalid hex digits `{}` in `{}`", pair, input))?;
+        }
+
+        Ok(Fingerprint(fingerprint))
+    }
+}
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let pairs: Vec<String> = self.0.iter().map(|byte| format!("{:02X}", byte)).collect();
+        write!(f, "{}", pairs.join(":"))
+    }
+}
+
+impl TryFrom<String> for Fingerprint {
+    type Error = String;
+
+    fn try_from(input: String) -> Result<Self, Self::Error> {
+        input.parse()
+    }
+}
+
+impl From<Fingerprint> for String {
+    fn from(fingerprint: Fingerprint) -> Self {
+        fingerprint.to_string()
+    }
+}
+
+/// Returns a blocking HTTP client accepting only certificates with one of the `fingerprints` over HTTPS.
+pub fn blocking_client(fingerprints: &[Fingerprint]) -> reqwest::Result<reqwest::blocking::Client> {
+    reqwest::blocking::Client::builder()
+        .use_preconfigured_tls(tls_config(fingerprints))
+        .build()
+}
+
+fn tls_config(fingerprints: &[Fingerprint]) -> rustls::ClientConfig {
+    // explicitly *ring*, the process wide default provider is ambiguous if another crate enables aws-lc-rs
+    let provider = rustls::crypto::ring::default_provider();
+    let algorithms = provider.signature_verification_algorithms;
+    rustls::ClientConfig::builder_with_provider(Arc::new(provider))
+        .with_safe_default_protocol_versions()
+        .expect("ring supports the default protocol versions")
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(PinnedVerifier {
+            fingerprints: fingerprints.to_vec(),
+            algorithms,
+        }))
+        .with_no_client_auth()
+}
+
+/// Accepts the pinned certificates, the handshake signatures are verified as usual.
+#[derive(Debug)]
+struct PinnedVerifier {
+    fingerprints: Vec<Fingerprint>,
+    algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl ServerCertVerifier for PinnedVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if self.fingerprints.contains(&Fingerprint::of(end_entity)) {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::InvalidCertificate(CertificateError::ApplicationVerificationFailure))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
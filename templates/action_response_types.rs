@@ -1,11 +1,128 @@
 use serde::Deserialize;
 
+{{#each defaults as |default| }}
+/// Default value of the state variable `{{ default.variable }}` as given in the description, the string sent for it
+/// whatever the type of the state variable, e.g. `"1"` for a `boolean`.
+pub const {{ default.name }}: &str = {{{ default.literal }}};
+{{#if @last}}
+
+{{/if}}
+{{/each}}
+{{#each enums as |enum| }}
+/// Allowed values of the state variable `{{ enum.variable }}`.
+{{#if enum.unknown_variant}}
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum {{ enum.name }} {
+    {{#each enum.variants as |variant| }}
+    {{ variant.name }},
+    {{/each}}
+    /// A value the description doesn't list, e.g. added by a newer FRITZ!OS.
+    Unknown(String),
+}
+
+// deserialized via `FromStr`, serde's untagged variants aren't supported by every deserializer
+impl<'de> Deserialize<'de> for {{ enum.name }} {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+{{else}}
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum {{ enum.name }} {
+    {{#each enum.variants as |variant| }}
+    #[serde(rename = {{{ variant.literal }}})]
+    {{ variant.name }},
+    {{/each}}
+}
+{{/if}}
+
+impl std::fmt::Display for {{ enum.name }} {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            {{#each enum.variants as |variant| }}
+            {{ enum.name }}::{{ variant.name }} => {{{ variant.literal }}},
+            {{/each}}
+            {{#if enum.unknown_variant}}
+            {{ enum.name }}::Unknown(value) => value,
+            {{/if}}
+        })
+    }
+}
+
+impl std::str::FromStr for {{ enum.name }} {
+    type Err = {{ @root.multi_use }}::ParseValueError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            {{#each enum.variants as |variant| }}
+            {{{ variant.literal }}} => Ok({{ enum.name }}::{{ variant.name }}),
+            {{/each}}
+            {{#if enum.unknown_variant}}
+            value => Ok({{ enum.name }}::Unknown(value.to_string())),
+            {{else}}
+            value => Err({{ @root.multi_use }}::ParseValueError {
+                variable: "{{ enum.variable }}",
+                value: value.to_string(),
+            }),
+            {{/if}}
+        }
+    }
+}
+
+{{/each}}
+{{#each ranges as |range| }}
+/// Value of the state variable `{{ range.variable }}`, from `MIN` to `MAX` in steps of `STEP`.
+/// Responses aren't checked, a FRITZ!Box may send values outside of the range.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+pub struct {{ range.name }}({{ range.type }});
+
+impl {{ range.name }} {
+    pub const MIN: {{ range.type }} = {{ range.minimum }};
+    pub const MAX: {{ range.type }} = {{ range.maximum }};
+    pub const STEP: {{ range.type }} = {{ range.step }};
+
+    /// Returns `value` if it is between `MIN` and `MAX` and on a step.
+    pub fn new(value: {{ range.type }}) -> Result<Self, {{ @root.multi_use }}::RangeError> {
+        {{#if range.check_step}}
+        let off_step = (i128::from(value) - i128::from(Self::MIN)) % i128::from(Self::STEP) != 0;
+        if !(Self::MIN..=Self::MAX).contains(&value) || off_step {
+        {{else}}
+        if !(Self::MIN..=Self::MAX).contains(&value) {
+        {{/if}}
+            return Err({{ @root.multi_use }}::RangeError {
+                variable: "{{ range.variable }}",
+                value: value.into(),
+                minimum: Self::MIN.into(),
+                maximum: Self::MAX.into(),
+                step: Self::STEP.into(),
+            });
+        }
+        Ok({{ range.name }}(value))
+    }
+
+    pub fn get(self) -> {{ range.type }} {
+        self.0
+    }
+}
+
+impl std::fmt::Display for {{ range.name }} {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+{{/each}}
 {{#each actions as |action| }}
 #[derive(Deserialize, Debug)]
 pub struct {{ action.name }} {
     {{#each action.fields as |field | }}
     #[serde(rename = "{{ xml_name }}")]
-    pub {{field.name}}: {{type}},
+    {{#if field.deserialize_with}}
+    #[serde(deserialize_with = "{{ field.deserialize_with }}")]
+    {{/if}}
+    pub {{field.name}}: {{{type}}},
     {{/each}}
 }
 
@@ -0,0 +1,535 @@
+use std::sync::{Arc, Mutex};
+{{#if tls}}
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use sha2::Digest;
+{{/if}}
+use super::soap::{self, AccessRight, CallError, Fault, SoapAction, SoapChallenge, SoapHeader};
+
+/// Everything that can go wrong calling an action through a client.
+pub type ClientError = CallError<reqwest::Error>;
+{{#if blocking}}
+
+/// Calls the actions of a FRITZ!Box with blocking requests. The request files add a method per service,
+/// e.g. `client.tr064_device_info().get_info()`.
+#[derive(Debug, Clone)]
+pub struct FritzClient {
+    base_url: String,
+    authentication: Option<Arc<Authentication>>,
+    soap_authentication: Option<Arc<SoapAuthentication>>,
+    rights: Option<Vec<AccessRight>>,
+    http: reqwest::blocking::Client,
+}
+
+impl FritzClient {
+    /// Creates a client for the FRITZ!Box at `base_url`, e.g. `http://fritz.box:49000`.
+    pub fn new(base_url: &str) -> Self {
+        FritzClient {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            authentication: None,
+            soap_authentication: None,
+            rights: None,
+            http: reqwest::blocking::Client::new(),
+        }
+    }
+
+    /// Authenticates as `username` with HTTP digest auth. Clones of the client share the nonce.
+    pub fn with_credentials(mut self, username: &str, password: &str) -> Self {
+        self.authentication = Some(Arc::new(Authentication::new(username, password)));
+        self
+    }
+
+    /// Authenticates as `username` in the SOAP header with `InitChallenge` and `ClientAuth` instead of HTTP digest auth.
+    /// Clones of the client share the nonce.
+    pub fn with_soap_credentials(mut self, username: &str, password: &str) -> Self {
+        self.soap_authentication = Some(Arc::new(SoapAuthentication::new(username, password)));
+        self
+    }
+
+    /// Refuses to call actions needing a right not among `rights`, the rights of the user of `with_credentials`.
+    /// Actions the access rights the files were generated with don't list are always called.
+    pub fn with_rights(mut self, rights: &[AccessRight]) -> Self {
+        self.rights = Some(rights.to_vec());
+        self
+    }
+{{#if tls}}
+
+    /// Accepts only the certificates with one of the `fingerprints` over HTTPS, e.g. from `https://fritz.box:49443`.
+    /// The FRITZ!Box returns its HTTPS port with `GetSecurityPort` of `DeviceInfo`.
+    pub fn with_pinned_certificates(mut self, fingerprints: &[Fingerprint]) -> Result<Self, reqwest::Error> {
+        self.http = reqwest::blocking::Client::builder()
+            .use_preconfigured_tls(tls_config(fingerprints))
+            .build()?;
+        Ok(self)
+    }
+{{/if}}
+
+    /// Calls `action` and returns its response.
+    pub fn call<A: SoapAction>(&self, action: &A) -> Result<A::Response, ClientError> {
+        check_access::<A>(self.rights.as_deref())?;
+        let soap_action = action.soap_action_header();
+        let header = self.soap_authentication.as_ref().map(|authentication| authentication.header());
+        let (mut error, mut body) = self
+            .exchange(A::CONTROL_URL, &soap_action, &action.body_with_header(header.as_ref()))
+            .map_err(CallError::Transport)?;
+        if let Some(authentication) = &self.soap_authentication {
+            if authentication.challenged(&body) {
+                // the nonce was missing or expired, the response carries a new one
+                let header = authentication.header();
+                (error, body) = self
+                    .exchange(A::CONTROL_URL, &soap_action, &action.body_with_header(Some(&header)))
+                    .map_err(CallError::Transport)?;
+                authentication.challenged(&body);
+            }
+        }
+
+        match error {
+            Some(error) => Err(Fault::of(&body).map_or(CallError::Transport(error), CallError::Fault)),
+            None => soap::response::<A>(&body).map_err(CallError::Deserialize),
+        }
+    }
+
+    /// Posts `body` to `control_url`, answering an HTTP digest challenge. Returns the error of an error status next to
+    /// the response body, which may carry a new SOAP challenge.
+    fn exchange(&self, control_url: &str, soap_action: &str, body: &str) -> Result<(Option<reqwest::Error>, String), reqwest::Error> {
+        let mut response = self.send(control_url, soap_action, body)?;
+        if let Some(authentication) = &self.authentication {
+            if authentication.challenged(response.status(), response.headers()) {
+                response = self.send(control_url, soap_action, body)?;
+            }
+        }
+
+        let error = response.error_for_status_ref().err();
+        Ok((error, response.text()?))
+    }
+
+    fn send(&self, control_url: &str, soap_action: &str, body: &str) -> Result<reqwest::blocking::Response, reqwest::Error> {
+        let mut request = self
+            .http
+            .post(format!("{}{}", self.base_url, control_url))
+            .header("Content-Type", "text/xml; charset=\"utf-8\"")
+            .header("SOAPACTION", soap_action)
+            .body(body.to_string());
+        if let Some(authorization) = self.authentication.as_ref().and_then(|authentication| authentication.authorization(control_url)) {
+            request = request.header(reqwest::header::AUTHORIZATION, authorization);
+        }
+
+        request.send()
+    }
+}
+{{/if}}
+{{#if async}}
+
+/// Calls the actions of a FRITZ!Box with async requests. The request files add a method per service,
+/// e.g. `client.tr064_device_info().get_info().await`.
+#[derive(Debug, Clone)]
+pub struct AsyncFritzClient {
+    base_url: String,
+    authentication: Option<Arc<Authentication>>,
+    soap_authentication: Option<Arc<SoapAuthentication>>,
+    rights: Option<Vec<AccessRight>>,
+    http: reqwest::Client,
+}
+
+impl AsyncFritzClient {
+    /// Creates a client for the FRITZ!Box at `base_url`, e.g. `http://fritz.box:49000`.
+    pub fn new(base_url: &str) -> Self {
+        AsyncFritzClient {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            authentication: None,
+            soap_authentication: None,
+            rights: None,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Authenticates as `username` with HTTP digest auth. Clones of the client share the nonce.
+    pub fn with_credentials(mut self, username: &str, password: &str) -> Self {
+        self.authentication = Some(Arc::new(Authentication::new(username, password)));
+        self
+    }
+
+    /// Authenticates as `username` in the SOAP header with `InitChallenge` and `ClientAuth` instead of HTTP digest auth.
+    /// Clones of the client share the nonce.
+    pub fn with_soap_credentials(mut self, username: &str, password: &str) -> Self {
+        self.soap_authentication = Some(Arc::new(SoapAuthentication::new(username, password)));
+        self
+    }
+
+    /// Refuses to call actions needing a right not among `rights`, the rights of the user of `with_credentials`.
+    /// Actions the access rights the files were generated with don't list are always called.
+    pub fn with_rights(mut self, rights: &[AccessRight]) -> Self {
+        self.rights = Some(rights.to_vec());
+        self
+    }
+{{#if tls}}
+
+    /// Accepts only the certificates with one of the `fingerprints` over HTTPS, e.g. from `https://fritz.box:49443`.
+    /// The FRITZ!Box returns its HTTPS port with `GetSecurityPort` of `DeviceInfo`.
+    pub fn with_pinned_certificates(mut self, fingerprints: &[Fingerprint]) -> Result<Self, reqwest::Error> {
+        self.http = reqwest::Client::builder()
+            .use_preconfigured_tls(tls_config(fingerprints))
+            .build()?;
+        Ok(self)
+    }
+{{/if}}
+
+    /// Calls `action` and returns its response.
+    pub async fn call<A: SoapAction>(&self, action: &A) -> Result<A::Response, ClientError> {
+        check_access::<A>(self.rights.as_deref())?;
+        let soap_action = action.soap_action_header();
+        let header = self.soap_authentication.as_ref().map(|authentication| authentication.header());
+        let (mut error, mut body) = self
+            .exchange(A::CONTROL_URL, &soap_action, &action.body_with_header(header.as_ref()))
+            .await.map_err(CallError::Transport)?;
+        if let Some(authentication) = &self.soap_authentication {
+            if authentication.challenged(&body) {
+                // the nonce was missing or expired, the response carries a new one
+                let header = authentication.header();
+                (error, body) = self
+                    .exchange(A::CONTROL_URL, &soap_action, &action.body_with_header(Some(&header)))
+                    .await.map_err(CallError::Transport)?;
+                authentication.challenged(&body);
+            }
+        }
+
+        match error {
+            Some(error) => Err(Fault::of(&body).map_or(CallError::Transport(error), CallError::Fault)),
+            None => soap::response::<A>(&body).map_err(CallError::Deserialize),
+        }
+    }
+
+    /// Posts `body` to `control_url`, answering an HTTP digest challenge. Returns the error of an error status next to
+    /// the response body, which may carry a new SOAP challenge.
+    async fn exchange(&self, control_url: &str, soap_action: &str, body: &str) -> Result<(Option<reqwest::Error>, String), reqwest::Error> {
+        let mut response = self.send(control_url, soap_action, body).await?;
+        if let Some(authentication) = &self.authentication {
+            if authentication.challenged(response.status(), response.headers()) {
+                response = self.send(control_url, soap_action, body).await?;
+            }
+        }
+
+        let error = response.error_for_status_ref().err();
+        Ok((error, response.text().await?))
+    }
+
+    async fn send(&self, control_url: &str, soap_action: &str, body: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self
+            .http
+            .post(format!("{}{}", self.base_url, control_url))
+            .header("Content-Type", "text/xml; charset=\"utf-8\"")
+            .header("SOAPACTION", soap_action)
+            .body(body.to_string());
+        if let Some(authorization) = self.authentication.as_ref().and_then(|authentication| authentication.authorization(control_url)) {
+            request = request.header(reqwest::header::AUTHORIZATION, authorization);
+        }
+
+        request.send().await
+    }
+}
+{{/if}}
+
+/// Refuses `A` if it needs a right not among `rights`, unless `rights` are unknown.
+fn check_access<A: SoapAction>(rights: Option<&[AccessRight]>) -> Result<(), ClientError> {
+    match (A::ACCESS_RIGHT, rights) {
+        (Some(right), Some(rights)) if right != AccessRight::Anonymous && !rights.contains(&right) => {
+            Err(CallError::AccessDenied {
+                action: A::ACTION_NAME,
+                right,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Credentials and the last HTTP digest challenge of the FRITZ!Box.
+#[derive(Debug)]
+struct Authentication {
+    username: String,
+    password: String,
+    challenge: Mutex<Option<DigestChallenge>>,
+}
+
+/// A `WWW-Authenticate: Digest ..` challenge, its nonce is reused until the FRITZ!Box sends a new one.
+#[derive(Debug)]
+struct DigestChallenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    /// Whether `qop="auth"` was offered, the response then covers a client nonce and the nonce count.
+    qop_auth: bool,
+    nonce_count: u32,
+}
+
+impl Authentication {
+    fn new(username: &str, password: &str) -> Self {
+        Authentication {
+            username: username.to_string(),
+            password: password.to_string(),
+            challenge: Mutex::new(None),
+        }
+    }
+
+    /// Returns the `Authorization` header of a POST to `uri`, `None` until the first challenge arrived.
+    fn authorization(&self, uri: &str) -> Option<String> {
+        let mut challenge = self.challenge.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let challenge = challenge.as_mut()?;
+        challenge.nonce_count += 1;
+
+        let ha1 = md5_hex(&format!("{}:{}:{}", self.username, challenge.realm, self.password));
+        let ha2 = md5_hex(&format!("POST:{}", uri));
+        let mut authorization = format!(
+            "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", algorithm=MD5",
+            self.username, challenge.realm, challenge.nonce, uri
+        );
+        let response = if challenge.qop_auth {
+            let nonce_count = format!("{:08x}", challenge.nonce_count);
+            let cnonce = client_nonce(&challenge.nonce, challenge.nonce_count);
+            authorization.push_str(&format!(", qop=auth, nc={}, cnonce=\"{}\"", nonce_count, cnonce));
+            md5_hex(&format!("{}:{}:{}:{}:auth:{}", ha1, challenge.nonce, nonce_count, cnonce, ha2))
+        } else {
+            md5_hex(&format!("{}:{}:{}", ha1, challenge.nonce, ha2))
+        };
+        authorization.push_str(&format!(", response=\"{}\"", response));
+        if let Some(opaque) = &challenge.opaque {
+            authorization.push_str(&format!(", opaque=\"{}\"", opaque));
+        }
+
+        Some(authorization)
+    }
+
+    /// Remembers the digest challenge of an unauthorized response. Returns whether the request should be repeated,
+    /// which is the case if the challenge isn't the one the request was authorized with.
+    fn challenged(&self, status: reqwest::StatusCode, headers: &reqwest::header::HeaderMap) -> bool {
+        if status != reqwest::StatusCode::UNAUTHORIZED {
+            return false;
+        }
+        let new_challenge = headers
+            .get_all(reqwest::header::WWW_AUTHENTICATE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .find_map(DigestChallenge::parse);
+        let new_challenge = match new_challenge {
+            Some(new_challenge) => new_challenge,
+            None => return false,
+        };
+
+        let mut challenge = self.challenge.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        // the same nonce again means the credentials are wrong, a new one that the old nonce expired
+        let repeat = challenge.as_ref().is_none_or(|challenge| challenge.nonce != new_challenge.nonce);
+        *challenge = Some(new_challenge);
+        repeat
+    }
+}
+
+impl DigestChallenge {
+    /// Parses a `WWW-Authenticate` header value, `None` unless it's a digest challenge.
+    fn parse(value: &str) -> Option<DigestChallenge> {
+        let (scheme, parameters) = value.trim().split_once(' ')?;
+        if !scheme.eq_ignore_ascii_case("digest") {
+            return None;
+        }
+
+        let mut realm = None;
+        let mut nonce = None;
+        let mut opaque = None;
+        let mut qop_auth = false;
+        let mut rest = parameters.trim_start();
+        while let Some((name, after_name)) = rest.split_once('=') {
+            let after_name = after_name.trim_start();
+            let (value, after_value) = match after_name.strip_prefix('"') {
+                Some(quoted) => {
+                    let end = quoted.find('"')?;
+                    (&quoted[..end], &quoted[end + 1..])
+                }
+                None => {
+                    let end = after_name.find(',').unwrap_or(after_name.len());
+                    (after_name[..end].trim_end(), &after_name[end..])
+                }
+            };
+            match name.trim().to_ascii_lowercase().as_str() {
+                "realm" => realm = Some(value.to_string()),
+                "nonce" => nonce = Some(value.to_string()),
+                "opaque" => opaque = Some(value.to_string()),
+                "qop" => qop_auth = value.split(',').any(|qop| qop.trim().eq_ignore_ascii_case("auth")),
+                _ => {}
+            }
+            rest = after_value.trim_start().trim_start_matches(',').trim_start();
+        }
+
+        Some(DigestChallenge {
+            realm: realm?,
+            nonce: nonce?,
+            opaque,
+            qop_auth,
+            nonce_count: 0,
+        })
+    }
+}
+
+/// Credentials and the last nonce of the authentication in the SOAP header.
+#[derive(Debug)]
+struct SoapAuthentication {
+    username: String,
+    password: String,
+    challenge: Mutex<Option<SoapChallenge>>,
+}
+
+impl SoapAuthentication {
+    fn new(username: &str, password: &str) -> Self {
+        SoapAuthentication {
+            username: username.to_string(),
+            password: password.to_string(),
+            challenge: Mutex::new(None),
+        }
+    }
+
+    /// Returns the SOAP header of the next request, `ClientAuth` answering the last challenge or `InitChallenge`.
+    fn header(&self) -> SoapHeader {
+        let challenge = self.challenge.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match challenge.as_ref() {
+            Some(challenge) => {
+                let secret = md5_hex(&format!("{}:{}:{}", self.username, challenge.realm, self.password));
+                SoapHeader::ClientAuth {
+                    nonce: challenge.nonce.clone(),
+                    auth: md5_hex(&format!("{}:{}", secret, challenge.nonce)),
+                    user_id: self.username.clone(),
+                    realm: challenge.realm.clone(),
+                }
+            }
+            None => SoapHeader::InitChallenge {
+                user_id: self.username.clone(),
+            },
+        }
+    }
+
+    /// Remembers the challenge in the response envelope `body`. Returns whether the request was refused and has to be
+    /// repeated with the new nonce.
+    fn challenged(&self, body: &str) -> bool {
+        let new_challenge = match SoapChallenge::of(body) {
+            Some(new_challenge) => new_challenge,
+            None => return false,
+        };
+        let refused = new_challenge.is_unauthenticated();
+        *self.challenge.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(new_challenge);
+        refused
+    }
+}
+
+fn md5_hex(value: &str) -> String {
+    format!("{:x}", md5::compute(value))
+}
+
+/// Returns a client nonce that differs per request.
+fn client_nonce(nonce: &str, nonce_count: u32) -> String {
+    let time = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    md5_hex(&format!("{}:{}:{}", nonce, nonce_count, time))[..16].to_string()
+}
+{{#if tls}}
+
+/// SHA-256 fingerprint of a certificate the clients accept over HTTPS, parsed from hex digits optionally separated by
+/// colons like `openssl x509 -fingerprint -sha256` prints them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fingerprint([u8; 32]);
+
+impl Fingerprint {
+    /// Fingerprint of the DER encoded `certificate`.
+    pub fn of(certificate: &[u8]) -> Self {
+        Fingerprint(sha2::Sha256::digest(certificate).into())
+    }
+
+    /// Fingerprints of the certificates in the PEM file content `pem`, e.g. the certificate exported from the
+    /// FRITZ!Box. Fails if it doesn't contain any.
+    pub fn of_pem(mut pem: &[u8]) -> std::io::Result<Vec<Fingerprint>> {
+        let fingerprints = rustls_pemfile::certs(&mut pem)
+            .map(|certificate| certificate.map(|certificate| Fingerprint::of(&certificate)))
+            .collect::<std::io::Result<Vec<Fingerprint>>>()?;
+        if fingerprints.is_empty() {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "no certificate found"));
+        }
+
+        Ok(fingerprints)
+    }
+}
+
+impl std::str::FromStr for Fingerprint {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let digits: Vec<u8> = input.bytes().filter(|digit| *digit != b':').collect();
+        let mut fingerprint = [0; 32];
+        if digits.len() != fingerprint.len() * 2 {
+            return Err(format!("expected a SHA-256 fingerprint of 64 hex digits, got `{}`", input));
+        }
+        for (byte, pair) in fingerprint.iter_mut().zip(digits.chunks(2)) {
+            let pair = std::str::from_utf8(pair).map_err(|e| e.to_string())?;
+            *byte = u8::from_str_radix(pair, 16).map_err(|_| format!("invalid hex digits `{}` in `{}`", pair, input))?;
+        }
+
+        Ok(Fingerprint(fingerprint))
+    }
+}
+
+fn tls_config(fingerprints: &[Fingerprint]) -> rustls::ClientConfig {
+    let provider = rustls::crypto::ring::default_provider();
+    let algorithms = provider.signature_verification_algorithms;
+    rustls::ClientConfig::builder_with_provider(Arc::new(provider))
+        .with_safe_default_protocol_versions()
+        .expect("ring supports the default protocol versions")
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(PinnedVerifier {
+            fingerprints: fingerprints.to_vec(),
+            algorithms,
+        }))
+        .with_no_client_auth()
+}
+
+/// Accepts the pinned certificates instead of verifying a chain and the host name, the FRITZ!Box signs its own.
+#[derive(Debug)]
+struct PinnedVerifier {
+    fingerprints: Vec<Fingerprint>,
+    algorithms: rustls::crypto::WebPkiSupportedAlgorithms,
+}
+
+impl ServerCertVerifier for PinnedVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if self.fingerprints.contains(&Fingerprint::of(end_entity)) {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::InvalidCertificate(rustls::CertificateError::ApplicationVerificationFailure))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
+{{/if}}
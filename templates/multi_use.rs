@@ -14,4 +14,101 @@ pub struct Body<T> {
     {{/each}}
     )]
     pub response: T,
-}
\ No newline at end of file
+}
+
+/// An envelope holding either the response `T` or the `Fault` the FRITZ!Box answered a failed action with.
+#[derive(Deserialize, Debug)]
+pub struct ResultEnvelope<T> {
+    #[serde(rename = "Body")]
+    pub body: ResultBody<T>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResultBody<T> {
+    #[serde(rename = "Fault")]
+    pub fault: Option<super::super::soap::Fault>,
+    #[serde(
+    {{#each actions as |action_name| }}
+        alias = "{{ action_name }}Response",
+    {{/each}}
+    )]
+    pub response: Option<T>,
+}
+
+impl<T> ResultEnvelope<T> {
+    /// Returns the response or the fault, `None` if the body holds neither.
+    pub fn into_result(self) -> Option<Result<T, super::super::soap::Fault>> {
+        match self.body.fault {
+            Some(fault) => Some(Err(fault)),
+            None => self.body.response.map(Ok),
+        }
+    }
+}
+{{~#if glue.parse_value_error}}
+
+/// A value that isn't one of the allowed values of the state variable `variable`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseValueError {
+    pub variable: &'static str,
+    pub value: String,
+}
+
+impl std::fmt::Display for ParseValueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}` isn't an allowed value of {}", self.value, self.variable)
+    }
+}
+
+impl std::error::Error for ParseValueError {}
+{{~/if}}
+{{~#if glue.range_error}}
+
+/// A value outside of the allowed range of the state variable `variable` or not on a step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeError {
+    pub variable: &'static str,
+    pub value: i128,
+    pub minimum: i128,
+    pub maximum: i128,
+    pub step: i128,
+}
+
+impl std::fmt::Display for RangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} isn't an allowed value of {}, expected {} to {} in steps of {}",
+            self.value, self.variable, self.minimum, self.maximum, self.step
+        )
+    }
+}
+
+impl std::error::Error for RangeError {}
+{{~/if}}
+{{~#if glue.deserialize_date_time}}
+
+/// Deserializes a `dateTime`, a time zone sent along is dropped.
+pub fn deserialize_date_time<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<chrono::NaiveDateTime, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    chrono::DateTime::parse_from_rfc3339(&value)
+        .map(|date_time| date_time.naive_local())
+        .or_else(|_| chrono::NaiveDateTime::parse_from_str(&value, "%Y-%m-%dT%H:%M:%S%.f"))
+        .map_err(serde::de::Error::custom)
+}
+{{~/if}}
+{{~#if glue.deserialize_base64}}
+
+/// Deserializes a `bin.base64`.
+pub fn deserialize_base64<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    base64::Engine::decode(&base64::engine::general_purpose::STANDARD, value.trim()).map_err(serde::de::Error::custom)
+}
+{{~/if}}
+{{~#if glue.deserialize_hex}}
+
+/// Deserializes a `bin.hex`.
+pub fn deserialize_hex<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    hex::decode(value.trim()).map_err(serde::de::Error::custom)
+}
+{{~/if}}
\ No newline at end of file
@@ -1,28 +1,151 @@
+{{#*inline "parameters"}}
+    {{~ #each parameter as |parameter| ~}}
+    {{~ parameter.parameter_name_rusty }}: {{#if parameter.optional}}Option<{{{ borrowed_type parameter.type_name }}}>{{else}}{{{ borrowed_type parameter.type_name }}}{{/if}}{{#unless @last}}, {{/unless}}
+    {{~ /each ~}}
+{{/inline}}
+use super::super::soap::SoapAction;
 {{#each request_functions as |func| }}
-pub fn generate_{{ func.name_rusty }}_request(
-    {{~ #each func.parameter as |parameter| ~}}
-    {{~ parameter.parameter_name_rusty }}: {{{ string_to_string_slice parameter.type_name }}}{{#unless @last}},{{/unless}}
-    {{~ /each }}) -> (String, String, String) {
-
-    let uri = "{{ func.control_url }}";
-    let header = format!("{{ func.service_type }}#{{func.action_name}}");
-
-    let body = format!(
-    r#"<?xml version="1.0">
-    <s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"s:encodingStyle="http://schemas.xmlsoap.org/soap/encoding/">
-        <s:Body>
-            <u:{{ func.action_name }} xmlns:u="{{ func.service_type }}">
-                {{ #each func.parameter as |parameter| }}
-                <{{ parameter.parameter_name }}>{}</{{ parameter.parameter_name }}>
-                {{ /each }}
-            </u:{{ func.action_name }}>
-        </s:Body>
-    </s:Envelope>"#{{~#each func.parameter as |parameter| ~}}
-                       , {{ parameter.parameter_name_rusty ~}}
-                      {{~ /each }});
-
-    (uri.to_string(), header, body)
+{{#if func.access_right}}
+/// Needs the access right `{{ func.access_right }}`.
+{{/if}}
+pub fn generate_{{ func.name_rusty }}_request({{> parameters}}) -> (String, String, String) {
+
+    {{#if func.parameter}}
+    let request = {{ func.struct_name }} {
+        {{#each func.parameter as |parameter| }}
+        {{ parameter.parameter_name_rusty }}{{#if parameter.owned_argument}}: {{{ parameter.owned_argument }}}{{/if}},
+        {{/each}}
+    };
+    {{else}}
+    let request = {{ func.struct_name }};
+    {{/if}}
+
+    ({{ func.struct_name }}::CONTROL_URL.to_string(), request.soap_action_header(), request.body())
+}
+
+/// Arguments of the action `{{ func.action_name }}`.
+{{#if func.access_right}}
+///
+/// Needs the access right `{{ func.access_right }}`.
+{{/if}}
+#[derive(Debug)]
+{{#if func.parameter}}
+pub struct {{ func.struct_name }} {
+    {{#each func.parameter as |parameter| }}
+    pub {{ parameter.parameter_name_rusty }}: {{#if parameter.optional}}Option<{{{ parameter.type_name }}}>{{else}}{{{ parameter.type_name }}}{{/if}},
+    {{/each}}
+}
+{{else}}
+pub struct {{ func.struct_name }};
+{{/if}}
+
+impl SoapAction for {{ func.struct_name }} {
+    type Response = {{{ func.response_type }}};
+
+    const CONTROL_URL: &'static str = "{{ func.control_url }}";
+    const SERVICE_TYPE: &'static str = "{{ func.service_type }}";
+    const ACTION_NAME: &'static str = "{{ func.action_name }}";
+    {{#if func.access_right}}
+    const ACCESS_RIGHT: Option<super::super::soap::AccessRight> = Some(super::super::soap::AccessRight::{{ func.access_right }});
+    {{/if}}
+
+    fn body_with_header(&self, header: Option<&super::super::soap::SoapHeader>) -> String {
+        {{#if func.parameter}}
+        let {{ func.struct_name }} {
+            {{#each func.parameter as |parameter| }}
+            {{ parameter.parameter_name_rusty }},
+            {{/each}}
+        } = self;
+        super::{{ @root.multi_use }}::envelope_with_header(
+            header,
+            Self::SERVICE_TYPE,
+            Self::ACTION_NAME,
+            &[
+                {{#each func.parameter as |parameter| }}
+                ("{{ parameter.parameter_name }}", {{{ parameter.format_argument }}}.to_string()),
+                {{/each}}
+            ],
+        )
+        {{else}}
+        super::{{ @root.multi_use }}::envelope_with_header(header, Self::SERVICE_TYPE, Self::ACTION_NAME, &[])
+        {{/if}}
+    }
 }
 
 {{/each}}
+{{#if client.blocking}}
+/// Calls the actions of the service through a `FritzClient`.
+pub struct Client<'c> {
+    client: &'c super::super::client::FritzClient,
+}
 
+impl super::super::client::FritzClient {
+    /// Calls the actions of the service of `{{ module }}`.
+    pub fn {{ module }}(&self) -> Client<'_> {
+        Client { client: self }
+    }
+}
+
+impl Client<'_> {
+    {{#each request_functions as |func| }}
+    /// Calls `{{ func.action_name }}`.
+    {{#if func.access_right}}
+    ///
+    /// Needs the access right `{{ func.access_right }}`.
+    {{/if}}
+    pub fn {{ func.name_rusty }}(&self{{#if func.parameter}}, {{/if}}{{> parameters}}) -> Result<{{{ func.response_type }}}, super::super::client::ClientError> {
+        {{#if func.parameter}}
+        let request = {{ func.struct_name }} {
+            {{#each func.parameter as |parameter| }}
+            {{ parameter.parameter_name_rusty }}{{#if parameter.owned_argument}}: {{{ parameter.owned_argument }}}{{/if}},
+            {{/each}}
+        };
+        {{else}}
+        let request = {{ func.struct_name }};
+        {{/if}}
+        self.client.call(&request)
+    }
+    {{#unless @last}}
+
+    {{/unless}}
+    {{/each}}
+}
+{{/if}}
+{{#if client.async}}
+/// Calls the actions of the service through an `AsyncFritzClient`.
+pub struct AsyncClient<'c> {
+    client: &'c super::super::client::AsyncFritzClient,
+}
+
+impl super::super::client::AsyncFritzClient {
+    /// Calls the actions of the service of `{{ module }}`.
+    pub fn {{ module }}(&self) -> AsyncClient<'_> {
+        AsyncClient { client: self }
+    }
+}
+
+impl AsyncClient<'_> {
+    {{#each request_functions as |func| }}
+    /// Calls `{{ func.action_name }}`.
+    {{#if func.access_right}}
+    ///
+    /// Needs the access right `{{ func.access_right }}`.
+    {{/if}}
+    pub async fn {{ func.name_rusty }}(&self{{#if func.parameter}}, {{/if}}{{> parameters}}) -> Result<{{{ func.response_type }}}, super::super::client::ClientError> {
+        {{#if func.parameter}}
+        let request = {{ func.struct_name }} {
+            {{#each func.parameter as |parameter| }}
+            {{ parameter.parameter_name_rusty }}{{#if parameter.owned_argument}}: {{{ parameter.owned_argument }}}{{/if}},
+            {{/each}}
+        };
+        {{else}}
+        let request = {{ func.struct_name }};
+        {{/if}}
+        self.client.call(&request).await
+    }
+    {{#unless @last}}
+
+    {{/unless}}
+    {{/each}}
+}
+{{/if}}
@@ -0,0 +1,74 @@
+/// Escapes `value` for XML text and attribute values.
+/// `\r` is escaped as well, a parser would normalize it to `\n` otherwise.
+/// Characters XML 1.0 can't represent, not even escaped, are replaced with `U+FFFD`: the control characters other than
+/// tab, line feed and carriage return, `U+FFFE` and `U+FFFF`.
+pub fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\t' | '\n' => escaped.push(c),
+            '\u{0}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}' => escaped.push(char::REPLACEMENT_CHARACTER),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Builds the SOAP envelope calling `action` of `service_type` with `arguments`, every value is escaped.
+pub fn envelope(service_type: &str, action: &str, arguments: &[(&str, String)]) -> String {
+    envelope_with_header(None, service_type, action, arguments)
+}
+
+/// Builds the SOAP envelope like `envelope`, with `header` as SOAP header.
+pub fn envelope_with_header(
+    header: Option<&super::super::soap::SoapHeader>,
+    service_type: &str,
+    action: &str,
+    arguments: &[(&str, String)],
+) -> String {
+    let mut body = String::from(concat!(
+        "<?xml version=\"1.0\"?>\n",
+        "<s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" ",
+        "s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\n",
+    ));
+    if let Some(header) = header {
+        body.push_str(&soap_header(header));
+    }
+    body.push_str("<s:Body>\n");
+    body.push_str(&format!("<u:{} xmlns:u=\"{}\">\n", action, escape_xml(service_type)));
+    for (name, value) in arguments {
+        body.push_str(&format!("<{}>{}</{}>\n", name, escape_xml(value), name));
+    }
+    body.push_str(&format!("</u:{}>\n</s:Body>\n</s:Envelope>\n", action));
+
+    body
+}
+
+/// Builds the `s:Header` element carrying `header`.
+fn soap_header(header: &super::super::soap::SoapHeader) -> String {
+    use super::super::soap::{SoapHeader, AUTHENTICATION_NAMESPACE};
+
+    let (element, values) = match header {
+        SoapHeader::InitChallenge { user_id } => ("InitChallenge", vec![("UserID", user_id)]),
+        SoapHeader::ClientAuth { nonce, auth, user_id, realm } => (
+            "ClientAuth",
+            vec![("Nonce", nonce), ("Auth", auth), ("UserID", user_id), ("Realm", realm)],
+        ),
+    };
+    let mut header = format!(
+        "<s:Header>\n<h:{} xmlns:h=\"{}\" s:mustUnderstand=\"1\">\n",
+        element, AUTHENTICATION_NAMESPACE
+    );
+    for (name, value) in values {
+        header.push_str(&format!("<{}>{}</{}>\n", name, escape_xml(value), name));
+    }
+    header.push_str(&format!("</h:{}>\n</s:Header>\n", element));
+
+    header
+}
@@ -0,0 +1,339 @@
+use serde::Deserialize;
+
+/// An action of a service, implemented by the request structs of the requests folders.
+pub trait SoapAction {
+    /// The struct the response deserializes into, wrapped in the `Envelope` of the responses folder.
+    type Response: serde::de::DeserializeOwned;
+
+    const CONTROL_URL: &'static str;
+    const SERVICE_TYPE: &'static str;
+    const ACTION_NAME: &'static str;
+    /// The right needed to call the action, `None` if the access rights the files were generated with don't list it.
+    const ACCESS_RIGHT: Option<AccessRight> = None;
+
+    /// Returns the SOAP envelope calling the action with the arguments of `self` and `header` as SOAP header.
+    fn body_with_header(&self, header: Option<&SoapHeader>) -> String;
+
+    /// Returns the SOAP envelope calling the action with the arguments of `self`.
+    fn body(&self) -> String {
+        self.body_with_header(None)
+    }
+
+    /// Returns the value of the `SOAPAction` header, `SERVICE_TYPE#ACTION_NAME`.
+    fn soap_action_header(&self) -> String {
+        format!("{}#{}", Self::SERVICE_TYPE, Self::ACTION_NAME)
+    }
+}
+
+/// Right a FRITZ!Box user needs to call an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessRight {
+    /// Callable without login.
+    Anonymous,
+    /// Access from FRITZ!Apps.
+    App,
+    /// FRITZ!Box settings.
+    Settings,
+    /// Voice messages, fax messages, FRITZ!App Fon and call list.
+    Phone,
+    /// Access to NAS contents.
+    Nas,
+    /// Smart Home.
+    SmartHome,
+}
+
+/// Namespace of the authentication in the SOAP header defined by TR-064.
+pub const AUTHENTICATION_NAMESPACE: &str = "http://soap-authentication.org/digest/2001/10/";
+
+/// Authentication in the SOAP header of a request, the alternative to HTTP digest auth defined by TR-064.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SoapHeader {
+    /// Asks for a nonce, answered with a `SoapChallenge`.
+    InitChallenge { user_id: String },
+    /// Answers the last `SoapChallenge`, `auth` is `md5(md5(user_id:realm:password):nonce)` as lowercase hex.
+    ClientAuth {
+        nonce: String,
+        auth: String,
+        user_id: String,
+        realm: String,
+    },
+}
+
+/// The `Challenge` or `NextChallenge` in the SOAP header of a response, its nonce authenticates the next request.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SoapChallenge {
+    /// `Unauthenticated` if the request was refused, `Authenticated` otherwise.
+    #[serde(rename = "Status", default)]
+    pub status: String,
+    #[serde(rename = "Nonce")]
+    pub nonce: String,
+    #[serde(rename = "Realm")]
+    pub realm: String,
+}
+
+impl SoapChallenge {
+    /// Returns the challenge in the SOAP header of the response envelope `body`, if there is one.
+    pub fn of(body: &str) -> Option<SoapChallenge> {
+        let envelope: HeaderEnvelope = serde_xml_rs::from_str(body).ok()?;
+        let header = envelope.header?;
+        header.challenge.or(header.next_challenge)
+    }
+
+    /// Whether the request was refused, it has to be repeated answering this challenge.
+    pub fn is_unauthenticated(&self) -> bool {
+        self.status == "Unauthenticated"
+    }
+}
+
+/// The `s:Fault` a device answers a failed action with instead of the response.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Fault {
+    /// `s:Client` if the request was at fault, `s:Server` otherwise.
+    #[serde(rename = "faultcode")]
+    pub fault_code: String,
+    /// `UPnPError` if `detail` holds one.
+    #[serde(rename = "faultstring")]
+    pub fault_string: String,
+    pub detail: Option<FaultDetail>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FaultDetail {
+    #[serde(rename = "UPnPError")]
+    pub upnp_error: Option<UpnpError>,
+}
+
+/// Why an action failed, `error_code` is one of `ErrorCode`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct UpnpError {
+    #[serde(rename = "errorCode")]
+    pub error_code: u16,
+    #[serde(rename = "errorDescription", default)]
+    pub error_description: String,
+}
+
+impl Fault {
+    /// Returns the fault in the envelope `body`, if the device answered with one.
+    pub fn of(body: &str) -> Option<Fault> {
+        let envelope: FaultEnvelope = serde_xml_rs::from_str(body).ok()?;
+        envelope.body.fault
+    }
+
+    pub fn upnp_error(&self) -> Option<&UpnpError> {
+        self.detail.as_ref()?.upnp_error.as_ref()
+    }
+
+    /// The code of the `UPnPError`, `None` if the fault doesn't have one.
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        self.upnp_error().map(|upnp_error| ErrorCode::from(upnp_error.error_code))
+    }
+}
+
+impl std::fmt::Display for Fault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.upnp_error() {
+            Some(upnp_error) => write!(
+                f,
+                "{} {} ({:?}): {}",
+                self.fault_string,
+                upnp_error.error_code,
+                ErrorCode::from(upnp_error.error_code),
+                upnp_error.error_description
+            ),
+            None => write!(f, "{}: {}", self.fault_code, self.fault_string),
+        }
+    }
+}
+
+impl std::error::Error for Fault {}
+
+/// The codes of a `UPnPError`, the standard UPnP ones and those AVM added for TR-064.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// 401, the service has no action of that name.
+    InvalidAction,
+    /// 402, arguments are missing, in the wrong order or of the wrong type.
+    InvalidArgs,
+    /// 501, the device failed to complete the action.
+    ActionFailed,
+    /// 600, an argument value isn't valid.
+    ArgumentValueInvalid,
+    /// 601, an argument value is outside of the allowed range.
+    ArgumentValueOutOfRange,
+    /// 602, the optional action isn't implemented.
+    OptionalActionNotImplemented,
+    /// 603, the device doesn't have enough memory to complete the action.
+    OutOfMemory,
+    /// 604, the action needs human intervention.
+    HumanInterventionRequired,
+    /// 605, a string argument is too long.
+    StringArgumentTooLong,
+    /// 606, the user isn't allowed to call the action, see `AccessRight`.
+    ActionNotAuthorized,
+    /// 713, AVM: the index of an array entry is out of range.
+    SpecifiedArrayIndexInvalid,
+    /// 714, AVM: no array entry matches the arguments.
+    NoSuchEntryInArray,
+    /// 820, AVM: the FRITZ!Box failed internally.
+    InternalError,
+    /// 866, AVM: the action needs a second factor, e.g. pressing a button on the FRITZ!Box.
+    SecondFactorAuthenticationRequired,
+    /// 867, AVM: the second factor authentication is blocked for a while.
+    SecondFactorAuthenticationBlocked,
+    /// 868, AVM: another second factor authentication is in progress.
+    SecondFactorAuthenticationBusy,
+    /// Any other code.
+    Other(u16),
+}
+
+impl ErrorCode {
+    /// The numeric code, e.g. `401`.
+    pub fn code(&self) -> u16 {
+        match self {
+            ErrorCode::InvalidAction => 401,
+            ErrorCode::InvalidArgs => 402,
+            ErrorCode::ActionFailed => 501,
+            ErrorCode::ArgumentValueInvalid => 600,
+            ErrorCode::ArgumentValueOutOfRange => 601,
+            ErrorCode::OptionalActionNotImplemented => 602,
+            ErrorCode::OutOfMemory => 603,
+            ErrorCode::HumanInterventionRequired => 604,
+            ErrorCode::StringArgumentTooLong => 605,
+            ErrorCode::ActionNotAuthorized => 606,
+            ErrorCode::SpecifiedArrayIndexInvalid => 713,
+            ErrorCode::NoSuchEntryInArray => 714,
+            ErrorCode::InternalError => 820,
+            ErrorCode::SecondFactorAuthenticationRequired => 866,
+            ErrorCode::SecondFactorAuthenticationBlocked => 867,
+            ErrorCode::SecondFactorAuthenticationBusy => 868,
+            ErrorCode::Other(code) => *code,
+        }
+    }
+}
+
+impl From<u16> for ErrorCode {
+    fn from(code: u16) -> Self {
+        match code {
+            401 => ErrorCode::InvalidAction,
+            402 => ErrorCode::InvalidArgs,
+            501 => ErrorCode::ActionFailed,
+            600 => ErrorCode::ArgumentValueInvalid,
+            601 => ErrorCode::ArgumentValueOutOfRange,
+            602 => ErrorCode::OptionalActionNotImplemented,
+            603 => ErrorCode::OutOfMemory,
+            604 => ErrorCode::HumanInterventionRequired,
+            605 => ErrorCode::StringArgumentTooLong,
+            606 => ErrorCode::ActionNotAuthorized,
+            713 => ErrorCode::SpecifiedArrayIndexInvalid,
+            714 => ErrorCode::NoSuchEntryInArray,
+            820 => ErrorCode::InternalError,
+            866 => ErrorCode::SecondFactorAuthenticationRequired,
+            867 => ErrorCode::SecondFactorAuthenticationBlocked,
+            868 => ErrorCode::SecondFactorAuthenticationBusy,
+            code => ErrorCode::Other(code),
+        }
+    }
+}
+
+/// Sends request bodies to the device, e.g. with an HTTP client.
+pub trait Transport {
+    type Error;
+
+    /// Posts `body` to `control_url` with `soap_action` as `SOAPAction` header and returns the response body. The body
+    /// of an error status is returned too, `call` reads the `Fault` out of it.
+    fn post(&mut self, control_url: &str, soap_action: &str, body: &str) -> Result<String, Self::Error>;
+}
+
+/// Everything that can go wrong in `call`.
+#[derive(Debug)]
+pub enum CallError<E> {
+    /// The transport couldn't send the request or receive the response.
+    Transport(E),
+    /// The response isn't an envelope containing the response of the action.
+    Deserialize(serde_xml_rs::Error),
+    /// The device answered with a fault, e.g. `ErrorCode::ActionNotAuthorized`.
+    Fault(Fault),
+    /// The action needs `right`, which the user of the client doesn't have. Nothing was sent.
+    AccessDenied {
+        action: &'static str,
+        right: AccessRight,
+    },
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for CallError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CallError::Transport(source) => write!(f, "failed to send request: {}", source),
+            CallError::Deserialize(source) => write!(f, "failed to deserialize response: {}", source),
+            CallError::Fault(fault) => write!(f, "action failed: {}", fault),
+            CallError::AccessDenied { action, right } => write!(f, "{} needs the access right {:?}", action, right),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for CallError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CallError::Transport(source) => Some(source),
+            CallError::Deserialize(source) => Some(source),
+            CallError::Fault(fault) => Some(fault),
+            CallError::AccessDenied { .. } => None,
+        }
+    }
+}
+
+/// Calls `action` through `transport` and returns its typed response.
+pub fn call<A: SoapAction, T: Transport>(transport: &mut T, action: &A) -> Result<A::Response, CallError<T::Error>> {
+    let response = transport
+        .post(A::CONTROL_URL, &action.soap_action_header(), &action.body())
+        .map_err(CallError::Transport)?;
+    if let Some(fault) = Fault::of(&response) {
+        return Err(CallError::Fault(fault));
+    }
+    self::response::<A>(&response).map_err(CallError::Deserialize)
+}
+
+/// Deserializes the response of the action `A` out of the envelope `body`.
+pub fn response<A: SoapAction>(body: &str) -> Result<A::Response, serde_xml_rs::Error> {
+    let envelope: Envelope<A::Response> = serde_xml_rs::from_str(body)?;
+    Ok(envelope.body.response)
+}
+
+#[derive(Deserialize)]
+struct Envelope<T> {
+    #[serde(rename = "Body")]
+    body: Body<T>,
+}
+
+#[derive(Deserialize)]
+struct Body<T> {
+    // the element is named after the action, e.g. `u:GetInfoResponse`
+    #[serde(rename = "$value")]
+    response: T,
+}
+
+#[derive(Deserialize)]
+struct HeaderEnvelope {
+    #[serde(rename = "Header")]
+    header: Option<Header>,
+}
+
+#[derive(Deserialize)]
+struct Header {
+    #[serde(rename = "Challenge")]
+    challenge: Option<SoapChallenge>,
+    #[serde(rename = "NextChallenge")]
+    next_challenge: Option<SoapChallenge>,
+}
+
+#[derive(Deserialize)]
+struct FaultEnvelope {
+    #[serde(rename = "Body")]
+    body: FaultBody,
+}
+
+#[derive(Deserialize)]
+struct FaultBody {
+    #[serde(rename = "Fault")]
+    fault: Option<Fault>,
+}
@@ -0,0 +1,90 @@
+//! Parses, formats and deserializes the enums generated from allowed values, with and without the `Unknown` variant.
+
+#[allow(dead_code)]
+mod api {
+    include!("fixtures/generated/allowed_values_api.rs");
+}
+
+#[allow(dead_code)]
+mod api_unknown {
+    include!("fixtures/generated/allowed_values_unknown_api.rs");
+}
+
+use api::soap::{response, SoapAction};
+use api::tr064_requests::tr064_x_allowed_values::{GetConnectionTypeRequest, SetConnectionTypeRequest};
+use api::tr064_responses::tr064_multi_use::ParseValueError;
+use api::tr064_responses::tr064_x_allowed_values::ConnectionType;
+use api_unknown::tr064_requests::tr064_x_allowed_values::GetConnectionTypeRequest as UnknownGetConnectionTypeRequest;
+use api_unknown::tr064_responses::tr064_x_allowed_values::ConnectionType as UnknownConnectionType;
+
+fn connection_type_response(connection_type: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\"?>\n\
+         <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\n\
+         <s:Body>\n<u:GetConnectionTypeResponse xmlns:u=\"urn:dslforum-org:service:X_AllowedValues:1\">\
+         <NewConnectionType>{}</NewConnectionType></u:GetConnectionTypeResponse>\n</s:Body>\n</s:Envelope>",
+        connection_type
+    )
+}
+
+#[test]
+fn known_values_round_trip() {
+    for (value, connection_type) in [
+        ("Unconfigured", ConnectionType::Unconfigured),
+        ("IP_Routed", ConnectionType::IpRouted),
+        ("IP_Bridged", ConnectionType::IpBridged),
+        ("IP-Routed", ConnectionType::IpRouted2),
+    ] {
+        assert_eq!(value.parse::<ConnectionType>(), Ok(connection_type.clone()));
+        assert_eq!(connection_type.to_string(), value);
+    }
+    assert_eq!("IP_Routed".parse(), Ok(UnknownConnectionType::IpRouted));
+    assert_eq!(UnknownConnectionType::IpRouted2.to_string(), "IP-Routed");
+}
+
+#[test]
+fn unknown_value_is_refused() {
+    let error = "PPPoE".parse::<ConnectionType>().unwrap_err();
+
+    assert_eq!(
+        error,
+        ParseValueError {
+            variable: "ConnectionType",
+            value: "PPPoE".to_string(),
+        }
+    );
+    assert_eq!(error.to_string(), "`PPPoE` isn't an allowed value of ConnectionType");
+    // allowed values are case sensitive
+    assert!("ip_routed".parse::<ConnectionType>().is_err());
+    assert!(response::<GetConnectionTypeRequest>(&connection_type_response("PPPoE")).is_err());
+}
+
+#[test]
+fn unknown_value_is_kept() {
+    let unknown: UnknownConnectionType = "PPPoE".parse().unwrap();
+
+    assert_eq!(unknown, UnknownConnectionType::Unknown("PPPoE".to_string()));
+    assert_eq!(unknown.to_string(), "PPPoE");
+    // a value named like the fallback is still unknown
+    assert_eq!("Unknown".parse(), Ok(UnknownConnectionType::Unknown("Unknown".to_string())));
+}
+
+#[test]
+fn values_are_deserialized() {
+    let known = response::<GetConnectionTypeRequest>(&connection_type_response("IP_Bridged")).unwrap();
+    let unknown =
+        api_unknown::soap::response::<UnknownGetConnectionTypeRequest>(&connection_type_response("PPPoE")).unwrap();
+
+    assert_eq!(known.new_connection_type, ConnectionType::IpBridged);
+    assert_eq!(unknown.new_connection_type, UnknownConnectionType::Unknown("PPPoE".to_string()));
+}
+
+#[test]
+fn values_are_formatted() {
+    let body = SetConnectionTypeRequest {
+        new_connection_type: ConnectionType::IpRouted2,
+    }
+    .body();
+
+    assert!(body.contains("<NewConnectionType>IP-Routed</NewConnectionType>"), "{}", body);
+}
@@ -0,0 +1,72 @@
+//! Generates the root module like a build script does and checks the `cargo:rerun-if-changed` lines it prints. The
+//! lines are printed to stdout, so the test runs `generate_into_out_dir` of this test binary as a child process.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use fritz_box_tr064_igd_api_files_generator::{build_script, Generator, MemorySink};
+use fritz_box_tr064_igd_api_files_generator::api_handling::source::DescriptionSource;
+
+const SNAPSHOT: &str = "tests/fixtures/snapshot";
+const OUT_DIR: &str = "FRITZGEN_TEST_OUT_DIR";
+
+/// Returns every file of `folder` and its sub folders.
+fn files(folder: &Path) -> Vec<PathBuf> {
+    let mut files = vec![];
+    for entry in fs::read_dir(folder).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(self::files(&path));
+        } else {
+            files.push(path);
+        }
+    }
+    files
+}
+
+#[test]
+#[ignore = "run by `root_module_is_written_and_snapshot_files_are_watched`"]
+fn generate_into_out_dir() {
+    build_script::generate(SNAPSHOT, env::var(OUT_DIR).unwrap()).unwrap();
+}
+
+#[test]
+fn root_module_is_written_and_snapshot_files_are_watched() {
+    let out_dir = env::temp_dir().join(format!("fritzgen-build-script-{}", std::process::id()));
+    fs::create_dir_all(&out_dir).unwrap();
+
+    let output = Command::new(env::current_exe().unwrap())
+        .args(["generate_into_out_dir", "--exact", "--ignored", "--nocapture", "--test-threads=1"])
+        .env(OUT_DIR, &out_dir)
+        .output()
+        .unwrap();
+    let root_module = fs::read_to_string(out_dir.join(build_script::ROOT_MODULE_FILE_NAME));
+    fs::remove_dir_all(&out_dir).unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{}{}", stdout, String::from_utf8_lossy(&output.stderr));
+    let mut watched: Vec<PathBuf> = stdout
+        .lines()
+        // the test harness prints the name of the test in front of the first line
+        .filter_map(|line| line.split_once("cargo:rerun-if-changed="))
+        .map(|(_, path)| PathBuf::from(path))
+        .collect();
+    watched.sort();
+    let mut snapshot_files = files(Path::new(SNAPSHOT));
+    snapshot_files.sort();
+    assert_eq!(watched, snapshot_files);
+
+    let mut sink = MemorySink::default();
+    Generator::new()
+        .source(DescriptionSource::Snapshot(SNAPSHOT.into()))
+        .sink(&mut sink)
+        .generate()
+        .unwrap();
+    let root_module = root_module.unwrap();
+    assert_eq!(root_module, sink.root_module());
+    let modules = ["pub mod soap {", "pub mod tr064_requests {", "pub mod igd_responses {", "pub mod tr064_device_config {"];
+    for module in modules {
+        assert!(root_module.contains(module), "{} is missing", module);
+    }
+}
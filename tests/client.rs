@@ -0,0 +1,476 @@
+//! Calls the generated clients against a local stand-in for the FRITZ!Box.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+#[allow(dead_code)]
+mod api {
+    include!("fixtures/generated/fritz_box_api.rs");
+}
+
+use api::client::{AsyncFritzClient, FritzClient};
+use api::soap::{AccessRight, CallError, ErrorCode};
+
+/// A request as received by the stand-in server.
+#[derive(Debug)]
+struct Received {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl Received {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A response of the stand-in server.
+struct Reply {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: String,
+}
+
+impl Reply {
+    fn new(status: u16, body: &str) -> Self {
+        Reply {
+            status,
+            headers: vec![],
+            body: body.to_string(),
+        }
+    }
+}
+
+/// Answers every request with `respond`, returns the base URL and the received requests.
+fn serve(mut respond: impl FnMut(&Received) -> Reply + Send + 'static) -> (String, Receiver<Received>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut reader = BufReader::new(stream.unwrap());
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let mut parts = line.split_whitespace();
+            let method = parts.next().unwrap_or_default().to_string();
+            let path = parts.next().unwrap_or_default().to_string();
+
+            let mut headers = vec![];
+            loop {
+                line.clear();
+                reader.read_line(&mut line).unwrap();
+                match line.trim_end().split_once(':') {
+                    Some((name, value)) => headers.push((name.to_string(), value.trim().to_string())),
+                    None => break,
+                }
+            }
+            let length = headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                .map_or(0, |(_, value)| value.parse().unwrap());
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            let received = Received {
+                method,
+                path,
+                headers,
+                body: String::from_utf8(body).unwrap(),
+            };
+
+            let reply = respond(&received);
+            // recorded before replying, the client only returns once every request of a call is recorded
+            if sender.send(received).is_err() {
+                break;
+            }
+            let mut stream = reader.into_inner();
+            write!(stream, "HTTP/1.1 {} Stand-in\r\nContent-Type: text/xml; charset=\"utf-8\"\r\n", reply.status).unwrap();
+            for (name, value) in &reply.headers {
+                write!(stream, "{}: {}\r\n", name, value).unwrap();
+            }
+            write!(stream, "Content-Length: {}\r\nConnection: close\r\n\r\n{}", reply.body.len(), reply.body).unwrap();
+        }
+    });
+
+    (base_url, receiver)
+}
+
+/// Answers the requests with `replies` in order.
+fn replay(replies: Vec<Reply>) -> impl FnMut(&Received) -> Reply + Send + 'static {
+    let mut replies = replies.into_iter();
+    move |_| replies.next().expect("more requests than replies")
+}
+
+const REALM: &str = "F!Box SOAP-Auth";
+
+/// Parses the parameters of an `Authorization: Digest ..` header, none of the values used here contain commas.
+fn digest_parameters(authorization: &str) -> HashMap<String, String> {
+    authorization
+        .trim_start_matches("Digest ")
+        .split(", ")
+        .filter_map(|parameter| parameter.split_once('='))
+        .map(|(name, value)| (name.to_string(), value.trim_matches('"').to_string()))
+        .collect()
+}
+
+fn md5_hex(value: &str) -> String {
+    format!("{:x}", md5::compute(value))
+}
+
+/// Stand-in for the digest authentication of the FRITZ!Box, accepting `password` of `admin`. Each nonce is good for
+/// `uses_per_nonce` requests, `qop` selects RFC 2617 or the older RFC 2069 responses.
+fn digest(password: &'static str, qop: bool, uses_per_nonce: u32, response: String) -> impl FnMut(&Received) -> Reply + Send + 'static {
+    let mut nonce_index = 1;
+    let mut uses = 0;
+
+    move |received| {
+        let nonce = format!("nonce{}", nonce_index);
+        let authorized = received.header("Authorization").map(digest_parameters).is_some_and(|parameters| {
+            let ha1 = md5_hex(&format!("admin:{}:{}", REALM, password));
+            let ha2 = md5_hex(&format!("POST:{}", received.path));
+            let expected = if qop {
+                md5_hex(&format!("{}:{}:{}:{}:auth:{}", ha1, nonce, parameters["nc"], parameters["cnonce"], ha2))
+            } else {
+                md5_hex(&format!("{}:{}:{}", ha1, nonce, ha2))
+            };
+            parameters["username"] == "admin"
+                && parameters["realm"] == REALM
+                && parameters["nonce"] == nonce
+                && parameters["uri"] == received.path
+                && parameters["response"] == expected
+        });
+
+        if authorized && uses < uses_per_nonce {
+            uses += 1;
+            return Reply::new(200, &response);
+        }
+        if authorized {
+            nonce_index += 1;
+            uses = 0;
+        }
+        let challenge = format!(
+            "Digest realm=\"{}\", nonce=\"nonce{}\", algorithm=MD5{}",
+            REALM,
+            nonce_index,
+            if qop { ", qop=\"auth\"" } else { "" }
+        );
+        Reply {
+            status: 401,
+            headers: vec![("WWW-Authenticate", challenge)],
+            body: String::new(),
+        }
+    }
+}
+
+/// Returns the text of the first element `name` in `xml`, ignoring its prefix.
+fn element<'x>(xml: &'x str, name: &str) -> Option<&'x str> {
+    let start = xml.find(&format!(":{} ", name)).or_else(|| xml.find(&format!("<{}>", name)))?;
+    let text = &xml[start..];
+    let text = &text[text.find('>')? + 1..];
+    Some(&text[..text.find('<')?])
+}
+
+/// Stand-in for the authentication in the SOAP header, accepting `password` of `admin`. Every authenticated request
+/// gets a new nonce, `response` is the body of the authenticated responses.
+fn soap_authentication(password: &'static str, response: &'static str) -> impl FnMut(&Received) -> Reply + Send + 'static {
+    let mut nonce_index = 1;
+
+    move |received| {
+        let nonce = format!("nonce{}", nonce_index);
+        let secret = md5_hex(&format!("admin:{}:{}", REALM, password));
+        let authorized = received.body.contains(":ClientAuth ")
+            && element(&received.body, "UserID") == Some("admin")
+            && element(&received.body, "Realm") == Some(REALM)
+            && element(&received.body, "Nonce") == Some(nonce.as_str())
+            && element(&received.body, "Auth") == Some(md5_hex(&format!("{}:{}", secret, nonce)).as_str());
+
+        let (status, challenge, body) = if authorized {
+            nonce_index += 1;
+            (200, "NextChallenge", response.to_string())
+        } else {
+            let fault = "<s:Fault><faultcode>s:Client</faultcode><faultstring>UPnPError</faultstring><detail>\
+                         <UPnPError xmlns=\"urn:schemas-upnp-org:control-1-0\"><errorCode>503</errorCode>\
+                         <errorDescription>Auth. failed</errorDescription></UPnPError></detail></s:Fault>";
+            (500, "Challenge", fault.to_string())
+        };
+        let header = format!(
+            "<h:{challenge} xmlns:h=\"http://soap-authentication.org/digest/2001/10/\" s:mustUnderstand=\"1\">\
+             <Status>{status}</Status><Nonce>nonce{nonce}</Nonce><Realm>{realm}</Realm></h:{challenge}>",
+            challenge = challenge,
+            status = if authorized { "Authenticated" } else { "Unauthenticated" },
+            nonce = nonce_index,
+            realm = REALM,
+        );
+        let envelope = format!(
+            "<?xml version=\"1.0\"?>\n\
+             <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\n\
+             <s:Header>{}</s:Header>\n<s:Body>{}</s:Body>\n</s:Envelope>",
+            header, body,
+        );
+        Reply::new(status, &envelope)
+    }
+}
+
+fn envelope(service_type: &str, response: &str, fields: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\"?>\n\
+         <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\n\
+         <s:Body>\n<u:{response} xmlns:u=\"{service_type}\">{fields}</u:{response}>\n</s:Body>\n</s:Envelope>",
+        response = response,
+        service_type = service_type,
+        fields = fields,
+    )
+}
+
+#[test]
+fn action_is_posted_and_response_deserialized() {
+    let response = envelope(
+        "urn:dslforum-org:service:WLANConfiguration:1",
+        "GetInfoResponse",
+        "<NewEnable>1</NewEnable><NewStatus>Up</NewStatus><NewChannel>11</NewChannel>\
+         <NewSSID>FRITZ!Box 7590</NewSSID><NewStandard>ac</NewStandard>",
+    );
+    let (base_url, received) = serve(replay(vec![Reply::new(200, &response)]));
+
+    let info = FritzClient::new(&base_url).tr064_wlan_configuration().get_info().unwrap();
+
+    assert_eq!(info.new_channel, 11);
+    assert_eq!(info.new_ssid, "FRITZ!Box 7590");
+    let request = received.recv().unwrap();
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, "/upnp/control/wlanconfig1");
+    assert_eq!(request.header("SOAPACTION"), Some("urn:dslforum-org:service:WLANConfiguration:1#GetInfo"));
+    assert_eq!(request.header("Content-Type"), Some("text/xml; charset=\"utf-8\""));
+    assert!(request.body.contains("<u:GetInfo xmlns:u=\"urn:dslforum-org:service:WLANConfiguration:1\">"));
+}
+
+#[test]
+fn arguments_are_sent() {
+    let response = envelope("urn:dslforum-org:service:WLANConfiguration:1", "SetSSIDResponse", "");
+    let (base_url, received) = serve(replay(vec![Reply::new(200, &response), Reply::new(200, &response)]));
+    let client = FritzClient::new(&format!("{}/", base_url));
+
+    client.tr064_wlan_configuration().set_ssid("a <b> & c").unwrap();
+    client.tr064_wlan_configuration().set_channel(6).unwrap();
+
+    assert!(received.recv().unwrap().body.contains("<NewSSID>a &lt;b&gt; &amp; c</NewSSID>"));
+    let request = received.recv().unwrap();
+    assert_eq!(request.header("SOAPACTION"), Some("urn:dslforum-org:service:WLANConfiguration:1#SetChannel"));
+    assert!(request.body.contains("<NewChannel>6</NewChannel>"));
+}
+
+#[test]
+fn error_status_fails() {
+    let (base_url, _received) = serve(replay(vec![Reply::new(401, "<html>Unauthorized</html>")]));
+
+    let error = FritzClient::new(&base_url).tr064_device_config().reboot().unwrap_err();
+
+    match error {
+        CallError::Transport(error) => assert_eq!(error.status().map(|status| status.as_u16()), Some(401)),
+        error => panic!("unexpected error {:?}", error),
+    }
+}
+
+#[test]
+fn fault_is_returned() {
+    let fault = "<?xml version=\"1.0\"?>\n\
+                 <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\n\
+                 <s:Body>\n<s:Fault>\n<faultcode>s:Client</faultcode>\n<faultstring>UPnPError</faultstring>\n<detail>\n\
+                 <UPnPError xmlns=\"urn:schemas-upnp-org:control-1-0\">\n<errorCode>606</errorCode>\n\
+                 <errorDescription>Action Not Authorized</errorDescription>\n</UPnPError>\n</detail>\n</s:Fault>\n</s:Body>\n</s:Envelope>";
+    let (base_url, _received) = serve(replay(vec![Reply::new(500, fault), Reply::new(500, "<html>Internal Error</html>")]));
+    let client = FritzClient::new(&base_url);
+
+    let error = client.tr064_device_config().reboot().unwrap_err();
+    let other = client.tr064_device_config().reboot().unwrap_err();
+
+    match error {
+        CallError::Fault(fault) => {
+            assert_eq!(fault.error_code(), Some(ErrorCode::ActionNotAuthorized));
+            assert_eq!(fault.upnp_error().unwrap().error_description, "Action Not Authorized");
+        }
+        error => panic!("unexpected error {:?}", error),
+    }
+    // an error status without a fault stays a transport error
+    assert!(matches!(other, CallError::Transport(_)), "unexpected error {:?}", other);
+}
+
+#[tokio::test]
+async fn async_client_shares_the_types() {
+    let response = envelope(
+        "urn:dslforum-org:service:DeviceConfig:1",
+        "GetPersistentDataResponse",
+        "<NewPersistentData>a &amp; b</NewPersistentData>",
+    );
+    let (base_url, received) = serve(replay(vec![Reply::new(200, &response), Reply::new(401, "")]));
+    let client = AsyncFritzClient::new(&base_url);
+
+    let data = client.tr064_device_config().get_persistent_data().await.unwrap();
+    let error = client.tr064_device_config().set_persistent_data(&data.new_persistent_data).await.unwrap_err();
+
+    assert_eq!(data.new_persistent_data, "a & b");
+    assert!(matches!(error, CallError::Transport(_)));
+    let request = received.recv().unwrap();
+    assert_eq!(request.path, "/upnp/control/deviceconfig");
+    assert_eq!(request.header("SOAPACTION"), Some("urn:dslforum-org:service:DeviceConfig:1#GetPersistentData"));
+    assert!(received.recv().unwrap().body.contains("<NewPersistentData>a &amp; b</NewPersistentData>"));
+}
+
+#[test]
+fn digest_handshake_reuses_the_nonce() {
+    let response = envelope("urn:dslforum-org:service:DeviceConfig:1", "RebootResponse", "");
+    let (base_url, received) = serve(digest("secret", true, 10, response));
+    let client = FritzClient::new(&base_url).with_credentials("admin", "secret");
+
+    client.tr064_device_config().reboot().unwrap();
+    client.clone().tr064_device_config().reboot().unwrap();
+
+    let requests: Vec<Received> = received.try_iter().collect();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0].header("Authorization"), None);
+    let first = digest_parameters(requests[1].header("Authorization").unwrap());
+    let second = digest_parameters(requests[2].header("Authorization").unwrap());
+    assert_eq!((first["nonce"].as_str(), first["nc"].as_str()), ("nonce1", "00000001"));
+    assert_eq!((second["nonce"].as_str(), second["nc"].as_str()), ("nonce1", "00000002"));
+    assert_ne!(first["cnonce"], second["cnonce"]);
+}
+
+#[test]
+fn expired_nonce_is_renewed() {
+    let response = envelope("urn:dslforum-org:service:DeviceConfig:1", "RebootResponse", "");
+    let (base_url, received) = serve(digest("secret", true, 1, response));
+    let client = FritzClient::new(&base_url).with_credentials("admin", "secret");
+
+    client.tr064_device_config().reboot().unwrap();
+    client.tr064_device_config().reboot().unwrap();
+
+    let nonces: Vec<Option<String>> = received
+        .try_iter()
+        .map(|request| request.header("Authorization").map(|authorization| digest_parameters(authorization)["nonce"].clone()))
+        .collect();
+    assert_eq!(
+        nonces,
+        vec![None, Some("nonce1".to_string()), Some("nonce1".to_string()), Some("nonce2".to_string())]
+    );
+}
+
+#[test]
+fn wrong_password_fails() {
+    let (base_url, received) = serve(digest("secret", true, 10, String::new()));
+
+    let error = FritzClient::new(&base_url)
+        .with_credentials("admin", "wrong")
+        .tr064_device_config()
+        .reboot()
+        .unwrap_err();
+
+    match error {
+        CallError::Transport(error) => assert_eq!(error.status().map(|status| status.as_u16()), Some(401)),
+        error => panic!("unexpected error {:?}", error),
+    }
+    // the challenge is answered once, the repeated nonce tells the credentials are wrong
+    assert_eq!(received.try_iter().count(), 2);
+}
+
+#[tokio::test]
+async fn async_client_authenticates() {
+    let response = envelope(
+        "urn:dslforum-org:service:DeviceConfig:1",
+        "GetPersistentDataResponse",
+        "<NewPersistentData>data</NewPersistentData>",
+    );
+    let (base_url, received) = serve(digest("secret", false, 10, response));
+    let client = AsyncFritzClient::new(&base_url).with_credentials("admin", "secret");
+
+    let data = client.tr064_device_config().get_persistent_data().await.unwrap();
+    client.tr064_device_config().get_persistent_data().await.unwrap();
+
+    assert_eq!(data.new_persistent_data, "data");
+    let requests: Vec<Received> = received.try_iter().collect();
+    assert_eq!(requests.len(), 3);
+    assert!(!digest_parameters(requests[2].header("Authorization").unwrap()).contains_key("qop"));
+}
+
+#[test]
+fn missing_right_is_refused() {
+    let response = envelope(
+        "urn:dslforum-org:service:WLANConfiguration:1",
+        "GetInfoResponse",
+        "<NewEnable>0</NewEnable><NewStatus>Disabled</NewStatus><NewChannel>1</NewChannel>\
+         <NewSSID></NewSSID><NewStandard>n</NewStandard>",
+    );
+    let (base_url, received) = serve(replay(vec![Reply::new(200, &response), Reply::new(200, &response)]));
+    let client = FritzClient::new(&base_url).with_rights(&[AccessRight::App]);
+
+    let error = client.tr064_device_config().reboot().unwrap_err();
+    client.tr064_wlan_configuration().get_info().unwrap();
+    // actions without a listed right are called
+    client.tr064_wlan_configuration().set_ssid("guest").unwrap();
+
+    match error {
+        CallError::AccessDenied { action, right } => assert_eq!((action, right), ("Reboot", AccessRight::Settings)),
+        error => panic!("unexpected error {:?}", error),
+    }
+    let paths: Vec<String> = received.try_iter().map(|request| request.path).collect();
+    assert_eq!(paths, vec!["/upnp/control/wlanconfig1", "/upnp/control/wlanconfig1"]);
+}
+
+#[test]
+fn soap_authentication_reuses_the_next_nonce() {
+    let response = "<u:RebootResponse xmlns:u=\"urn:dslforum-org:service:DeviceConfig:1\"></u:RebootResponse>";
+    let (base_url, received) = serve(soap_authentication("secret", response));
+    let client = FritzClient::new(&base_url).with_soap_credentials("admin", "secret");
+
+    client.tr064_device_config().reboot().unwrap();
+    client.clone().tr064_device_config().reboot().unwrap();
+
+    let requests: Vec<Received> = received.try_iter().collect();
+    assert_eq!(requests.len(), 3);
+    assert!(requests[0].body.contains("<h:InitChallenge xmlns:h=\"http://soap-authentication.org/digest/2001/10/\" s:mustUnderstand=\"1\">"));
+    assert_eq!(element(&requests[0].body, "UserID"), Some("admin"));
+    assert_eq!(element(&requests[0].body, "Nonce"), None);
+    assert_eq!(element(&requests[1].body, "Nonce"), Some("nonce1"));
+    assert_eq!(element(&requests[2].body, "Nonce"), Some("nonce2"));
+    assert!(requests.iter().all(|request| request.header("Authorization").is_none()));
+}
+
+#[test]
+fn wrong_soap_password_fails() {
+    let (base_url, received) = serve(soap_authentication("secret", ""));
+
+    let error = FritzClient::new(&base_url)
+        .with_soap_credentials("admin", "wrong")
+        .tr064_device_config()
+        .reboot()
+        .unwrap_err();
+
+    match error {
+        CallError::Fault(fault) => assert_eq!(fault.error_code(), Some(ErrorCode::Other(503))),
+        error => panic!("unexpected error {:?}", error),
+    }
+    assert_eq!(received.try_iter().count(), 2);
+}
+
+#[tokio::test]
+async fn async_client_authenticates_in_the_soap_header() {
+    let response = "<u:GetPersistentDataResponse xmlns:u=\"urn:dslforum-org:service:DeviceConfig:1\">\
+                    <NewPersistentData>data</NewPersistentData></u:GetPersistentDataResponse>";
+    let (base_url, received) = serve(soap_authentication("secret", response));
+    let client = AsyncFritzClient::new(&base_url).with_soap_credentials("admin", "secret");
+
+    let data = client.tr064_device_config().get_persistent_data().await.unwrap();
+    client.tr064_device_config().get_persistent_data().await.unwrap();
+
+    assert_eq!(data.new_persistent_data, "data");
+    let requests: Vec<Received> = received.try_iter().collect();
+    assert_eq!(requests.len(), 3);
+    assert!(requests[2].body.contains(":ClientAuth "));
+}
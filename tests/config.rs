@@ -0,0 +1,214 @@
+//! Loads configurations and access rights files and checks how their entries apply to services. The command line flags
+//! are merged over `fritzgen.toml` by the binary, checked with `generate --explain`.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use fritz_box_tr064_igd_api_files_generator::config::{AccessRight, Config, IntegerMapping, ServiceFilter};
+use fritz_box_tr064_igd_api_files_generator::GeneratorError;
+
+const ACCESS_RIGHTS: &str = "tests/fixtures/access_rights.toml";
+const TR064_WAN_IP: &str = "urn:dslforum-org:service:WANIPConnection:1";
+const IGD_WAN_IP: &str = "urn:schemas-upnp-org:service:WANIPConnection:1";
+const WLAN: &str = "urn:dslforum-org:service:WLANConfiguration:1";
+const DEVICE_CONFIG: &str = "urn:dslforum-org:service:DeviceConfig:1";
+
+const CONFIG: &str = r#"
+[source]
+address = "http://192.168.178.1:49000"
+
+[output]
+folder = "from_file"
+
+[services]
+include = ["WLANConfiguration", "DeviceConfig"]
+exclude = ["urn:dslforum-org:service:DeviceConfig:1"]
+
+[types]
+integers = "exact"
+"#;
+
+/// Runs the binary with `args` in a new folder containing `fritzgen.toml` with `CONFIG` and returns the configuration
+/// printed by `generate --explain`.
+fn explain(name: &str, args: &[&str]) -> Config {
+    let folder = env::temp_dir().join(format!("fritzgen-explain-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&folder).unwrap();
+    fs::write(folder.join("fritzgen.toml"), CONFIG).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_fritz_box_tr064_igd_api_files_generator"))
+        .current_dir(&folder)
+        .args(["generate", "--explain"])
+        .args(args)
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&folder).unwrap();
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    toml::from_str(&String::from_utf8(output.stdout).unwrap()).unwrap()
+}
+
+fn filter(include: &[&str], exclude: &[&str]) -> ServiceFilter {
+    ServiceFilter {
+        include: include.iter().map(|service| service.to_string()).collect(),
+        exclude: exclude.iter().map(|service| service.to_string()).collect(),
+    }
+}
+
+fn access_rights() -> Config {
+    let mut config = Config::default();
+    config.load_access_rights(Path::new(ACCESS_RIGHTS)).unwrap();
+    config
+}
+
+#[test]
+fn access_rights_are_loaded() {
+    let config = access_rights();
+
+    assert_eq!(config.access_right("urn:dslforum-org:service:DeviceConfig:1", "Reboot"), Some(AccessRight::Settings));
+    assert_eq!(
+        config.access_right("urn:dslforum-org:service:DeviceConfig:1", "X_AVM-DE_CreateUrlSID"),
+        Some(AccessRight::App)
+    );
+    assert_eq!(
+        config.access_right("urn:dslforum-org:service:X_AVM-DE_Homeauto:1", "GetGenericDeviceInfos"),
+        Some(AccessRight::SmartHome)
+    );
+    assert_eq!(
+        config.access_right("urn:dslforum-org:service:X_AVM-DE_OnTel:1", "GetCallList"),
+        Some(AccessRight::Phone)
+    );
+    assert_eq!(config.access_right("urn:dslforum-org:service:DeviceConfig:1", "GetInfo"), None);
+    assert_eq!(config.access_right("urn:dslforum-org:service:DeviceInfo:1", "Reboot"), None);
+}
+
+#[test]
+fn access_rights_are_added() {
+    let mut config = Config::default();
+    config.access_rights.entry("DeviceConfig".to_string()).or_default().insert("GetInfo".to_string(), AccessRight::App);
+
+    config.load_access_rights(Path::new(ACCESS_RIGHTS)).unwrap();
+
+    assert_eq!(config.access_right("urn:dslforum-org:service:DeviceConfig:1", "GetInfo"), Some(AccessRight::App));
+    assert_eq!(config.access_right("urn:dslforum-org:service:DeviceConfig:1", "Reboot"), Some(AccessRight::Settings));
+}
+
+#[test]
+fn service_type_takes_precedence_over_name() {
+    let config = access_rights();
+
+    // `urn:..` sorts after `WANIPConnection`, the order of the entries doesn't matter
+    assert_eq!(config.access_right(IGD_WAN_IP, "GetExternalIPAddress"), Some(AccessRight::Anonymous));
+    assert_eq!(config.access_right(TR064_WAN_IP, "GetExternalIPAddress"), Some(AccessRight::Settings));
+    // actions the service type doesn't list fall back to the service name
+    assert_eq!(config.access_right(IGD_WAN_IP, "GetStatusInfo"), Some(AccessRight::App));
+}
+
+#[test]
+fn invalid_access_rights_fail() {
+    let path = env::temp_dir().join(format!("fritzgen-access-rights-{}.toml", std::process::id()));
+    fs::write(&path, "[DeviceConfig]\nReboot = \"SmartHome\"\n").unwrap();
+
+    let invalid = Config::default().load_access_rights(&path);
+    fs::remove_file(&path).unwrap();
+    let missing = Config::default().load_access_rights(Path::new("tests/fixtures/missing.toml"));
+
+    assert!(matches!(invalid, Err(GeneratorError::Config { .. })), "unexpected result {:?}", invalid);
+    assert!(matches!(missing, Err(GeneratorError::Config { .. })), "unexpected result {:?}", missing);
+}
+
+#[test]
+fn type_override_without_selector_is_refused() {
+    let load = |name: &str, selector: &str| {
+        let path = env::temp_dir().join(format!("fritzgen-type-override-{}-{}.toml", name, std::process::id()));
+        let content = format!("[[type_override]]\nservice = \"Hosts\"\n{}type = \"crate::Mac\"\n", selector);
+        fs::write(&path, content).unwrap();
+        let result = Config::load(Some(&path));
+        fs::remove_file(&path).unwrap();
+        result
+    };
+
+    let without_selector = load("none", "");
+    let without_argument = load("action", "action = \"GetGenericHostEntry\"\n");
+    let argument = "action = \"GetGenericHostEntry\"\nargument = \"NewMACAddress\"\n";
+    let with_both = load("both", &format!("variable = \"MACAddress\"\n{}", argument));
+
+    for result in [without_selector, without_argument, with_both] {
+        assert!(matches!(result, Err(GeneratorError::Config { .. })), "unexpected result {:?}", result);
+    }
+    assert!(load("variable", "variable = \"MACAddress\"\n").is_ok());
+    assert!(load("argument", argument).is_ok());
+}
+
+#[test]
+fn empty_filter_includes_every_service() {
+    assert!(ServiceFilter::default().is_included(WLAN));
+    assert!(ServiceFilter::default().is_included(IGD_WAN_IP));
+}
+
+#[test]
+fn services_are_included_by_type_and_name() {
+    let by_type = filter(&[WLAN], &[]);
+    let by_name = filter(&["WLANConfiguration"], &[]);
+
+    assert!(by_type.is_included(WLAN));
+    assert!(!by_type.is_included("urn:dslforum-org:service:WLANConfiguration:2"));
+    assert!(!by_type.is_included(DEVICE_CONFIG));
+    // the name matches every version and both descriptions
+    assert!(by_name.is_included(WLAN));
+    assert!(by_name.is_included("urn:dslforum-org:service:WLANConfiguration:2"));
+    assert!(!by_name.is_included(DEVICE_CONFIG));
+    assert!(!filter(&["WLAN"], &[]).is_included(WLAN));
+    assert!(filter(&["WANIPConnection"], &[]).is_included(IGD_WAN_IP));
+}
+
+#[test]
+fn exclude_is_applied_after_include() {
+    let by_type = filter(&[], &[DEVICE_CONFIG]);
+    let by_name = filter(&["WLANConfiguration", "DeviceConfig"], &["DeviceConfig"]);
+
+    assert!(by_type.is_included(WLAN));
+    assert!(!by_type.is_included(DEVICE_CONFIG));
+    assert!(by_name.is_included(WLAN));
+    assert!(!by_name.is_included(DEVICE_CONFIG));
+}
+
+#[test]
+fn configuration_file_is_loaded() {
+    let config = explain("file", &[]);
+
+    assert_eq!(config.source.address, "http://192.168.178.1:49000");
+    assert_eq!(config.output.folder, "from_file");
+    assert_eq!(config.services.include, ["WLANConfiguration", "DeviceConfig"]);
+    assert!(config.services.is_included(WLAN));
+    assert!(!config.services.is_included(DEVICE_CONFIG));
+    assert_eq!(config.types.integers, IntegerMapping::Exact);
+    // keys missing in the file keep their defaults
+    assert_eq!(config.output.requests_folder, "requests");
+}
+
+#[test]
+fn flags_override_the_configuration_file() {
+    let config = explain(
+        "flags",
+        &[
+            "--output",
+            "from_flag",
+            "--include",
+            "DeviceConfig",
+            "--integers",
+            "widened",
+            "--snapshot",
+            "snapshots/7.57",
+        ],
+    );
+
+    assert_eq!(config.output.folder, "from_flag");
+    // lists are replaced, not extended
+    assert_eq!(config.services.include, ["DeviceConfig"]);
+    assert_eq!(config.services.exclude, [DEVICE_CONFIG]);
+    assert_eq!(config.types.integers, IntegerMapping::Widened);
+    assert_eq!(config.source.snapshot, Some(PathBuf::from("snapshots/7.57")));
+    // keys without a flag keep the value of the file
+    assert_eq!(config.source.address, "http://192.168.178.1:49000");
+}
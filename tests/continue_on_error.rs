@@ -0,0 +1,111 @@
+//! Generates from `tests/fixtures/broken`, which has a truncated SCPD and one referring to a missing state variable next
+//! to the valid `DeviceConfig`.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use fritz_box_tr064_igd_api_files_generator::{Generator, GeneratorError, MemorySink};
+use fritz_box_tr064_igd_api_files_generator::api_handling::source::DescriptionSource;
+use fritz_box_tr064_igd_api_files_generator::config::Description;
+
+const BROKEN: &str = "tests/fixtures/broken";
+
+fn generator(sink: &mut MemorySink) -> Generator<'_> {
+    Generator::new()
+        .source(DescriptionSource::Snapshot(BROKEN.into()))
+        .descriptions(&[Description::Tr064])
+        .sink(sink)
+}
+
+#[test]
+fn broken_services_are_skipped() {
+    let mut sink = MemorySink::default();
+
+    let result = generator(&mut sink).continue_on_error(true).generate().unwrap();
+
+    let skipped: Vec<(&str, &str)> = result
+        .skipped_services
+        .iter()
+        .map(|skipped| (skipped.service_type.as_str(), skipped.scpd_url.as_str()))
+        .collect();
+    assert_eq!(
+        skipped,
+        [
+            ("urn:dslforum-org:service:X_Truncated:1", "/x_truncatedSCPD.xml"),
+            ("urn:dslforum-org:service:X_Missing:1", "/x_missingSCPD.xml"),
+        ]
+    );
+    assert!(matches!(result.skipped_services[0].error, GeneratorError::Parse { .. }));
+    assert!(matches!(
+        &result.skipped_services[1].error,
+        GeneratorError::MissingStateVariable { variable, .. } if variable == "Enable"
+    ));
+    let generated: Vec<String> = sink.files.keys().map(|path| path.to_string_lossy().replace('\\', "/")).collect();
+    assert!(generated.iter().any(|path| path.ends_with("tr064_requests/tr064_device_config.rs")), "{:?}", generated);
+    assert!(!generated.iter().any(|path| path.contains("x_truncated") || path.contains("x_missing")), "{:?}", generated);
+}
+
+#[test]
+fn broken_service_fails_without_continue_on_error() {
+    let mut sink = MemorySink::default();
+
+    let error = generator(&mut sink).generate().unwrap_err();
+
+    match error {
+        GeneratorError::Service { service_type, scpd_url, .. } => {
+            assert_eq!(service_type, "urn:dslforum-org:service:X_Truncated:1");
+            assert_eq!(scpd_url, "/x_truncatedSCPD.xml");
+        }
+        error => panic!("unexpected error {:?}", error),
+    }
+}
+
+#[test]
+fn invalid_service_fails_with_its_scpd_url() {
+    let mut sink = MemorySink::default();
+
+    let error = generator(&mut sink).include_service("X_Missing").generate().unwrap_err();
+
+    match error {
+        GeneratorError::Service { service_type, scpd_url, source } => {
+            assert_eq!(service_type, "urn:dslforum-org:service:X_Missing:1");
+            assert_eq!(scpd_url, "/x_missingSCPD.xml");
+            assert!(matches!(*source, GeneratorError::MissingStateVariable { .. }), "{:?}", source);
+        }
+        error => panic!("unexpected error {:?}", error),
+    }
+}
+
+#[test]
+fn binary_exits_with_2_if_services_were_skipped() {
+    // run in an empty folder, so a `fritzgen.toml` of the working directory isn't read
+    let folder = env::temp_dir().join(format!("fritzgen-continue-on-error-{}", std::process::id()));
+    fs::create_dir_all(&folder).unwrap();
+    let snapshot = Path::new(env!("CARGO_MANIFEST_DIR")).join(BROKEN);
+    let generate = |continue_on_error: bool| {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_fritz_box_tr064_igd_api_files_generator"));
+        command
+            .current_dir(&folder)
+            .arg("generate")
+            .arg("--snapshot")
+            .arg(&snapshot)
+            .args(["--descriptions", "tr064"]);
+        if continue_on_error {
+            command.arg("--continue-on-error");
+        }
+        command.output().unwrap()
+    };
+
+    let skipping = generate(true);
+    let failing = generate(false);
+    let generated = folder.join("output/tr064_requests/tr064_device_config.rs").exists();
+    fs::remove_dir_all(&folder).unwrap();
+
+    let stderr = String::from_utf8_lossy(&skipping.stderr);
+    assert_eq!(skipping.status.code(), Some(2), "{}", stderr);
+    assert!(stderr.contains("urn:dslforum-org:service:X_Truncated:1"), "{}", stderr);
+    assert!(stderr.contains("urn:dslforum-org:service:X_Missing:1"), "{}", stderr);
+    assert!(generated);
+    assert_eq!(failing.status.code(), Some(1), "{}", String::from_utf8_lossy(&failing.stderr));
+}
@@ -0,0 +1,1764 @@
+pub mod client {
+use std::sync::{Arc, Mutex};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use sha2::Digest;
+use super::soap::{self, AccessRight, CallError, Fault, SoapAction, SoapChallenge, SoapHeader};
+
+/// Everything that can go wrong calling an action through a client.
+pub type ClientError = CallError<reqwest::Error>;
+
+/// Calls the actions of a FRITZ!Box with blocking requests. The request files add a method per service,
+/// e.g. `client.tr064_device_info().get_info()`.
+#[derive(Debug, Clone)]
+pub struct FritzClient {
+    base_url: String,
+    authentication: Option<Arc<Authentication>>,
+    soap_authentication: Option<Arc<SoapAuthentication>>,
+    rights: Option<Vec<AccessRight>>,
+    http: reqwest::blocking::Client,
+}
+
+impl FritzClient {
+    /// Creates a client for the FRITZ!Box at `base_url`, e.g. `http://fritz.box:49000`.
+    pub fn new(base_url: &str) -> Self {
+        FritzClient {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            authentication: None,
+            soap_authentication: None,
+            rights: None,
+            http: reqwest::blocking::Client::new(),
+        }
+    }
+
+    /// Authenticates as `username` with HTTP digest auth. Clones of the client share the nonce.
+    pub fn with_credentials(mut self, username: &str, password: &str) -> Self {
+        self.authentication = Some(Arc::new(Authentication::new(username, password)));
+        self
+    }
+
+    /// Authenticates as `username` in the SOAP header with `InitChallenge` and `ClientAuth` instead of HTTP digest auth.
+    /// Clones of the client share the nonce.
+    pub fn with_soap_credentials(mut self, username: &str, password: &str) -> Self {
+        self.soap_authentication = Some(Arc::new(SoapAuthentication::new(username, password)));
+        self
+    }
+
+    /// Refuses to call actions needing a right not among `rights`, the rights of the user of `with_credentials`.
+    /// Actions the access rights the files were generated with don't list are always called.
+    pub fn with_rights(mut self, rights: &[AccessRight]) -> Self {
+        self.rights = Some(rights.to_vec());
+        self
+    }
+
+    /// Accepts only the certificates with one of the `fingerprints` over HTTPS, e.g. from `https://fritz.box:49443`.
+    /// The FRITZ!Box returns its HTTPS port with `GetSecurityPort` of `DeviceInfo`.
+    pub fn with_pinned_certificates(mut self, fingerprints: &[Fingerprint]) -> Result<Self, reqwest::Error> {
+        self.http = reqwest::blocking::Client::builder()
+            .use_preconfigured_tls(tls_config(fingerprints))
+            .build()?;
+        Ok(self)
+    }
+
+    /// Calls `action` and returns its response.
+    pub fn call<A: SoapAction>(&self, action: &A) -> Result<A::Response, ClientError> {
+        check_access::<A>(self.rights.as_deref())?;
+        let soap_action = action.soap_action_header();
+        let header = self.soap_authentication.as_ref().map(|authentication| authentication.header());
+        let (mut error, mut body) = self
+            .exchange(A::CONTROL_URL, &soap_action, &action.body_with_header(header.as_ref()))
+            .map_err(CallError::Transport)?;
+        if let Some(authentication) = &self.soap_authentication {
+            if authentication.challenged(&body) {
+                // the nonce was missing or expired, the response carries a new one
+                let header = authentication.header();
+                (error, body) = self
+                    .exchange(A::CONTROL_URL, &soap_action, &action.body_with_header(Some(&header)))
+                    .map_err(CallError::Transport)?;
+                authentication.challenged(&body);
+            }
+        }
+
+        match error {
+            Some(error) => Err(Fault::of(&body).map_or(CallError::Transport(error), CallError::Fault)),
+            None => soap::response::<A>(&body).map_err(CallError::Deserialize),
+        }
+    }
+
+    /// Posts `body` to `control_url`, answering an HTTP digest challenge. Returns the error of an error status next to
+    /// the response body, which may carry a new SOAP challenge.
+    fn exchange(&self, control_url: &str, soap_action: &str, body: &str) -> Result<(Option<reqwest::Error>, String), reqwest::Error> {
+        let mut response = self.send(control_url, soap_action, body)?;
+        if let Some(authentication) = &self.authentication {
+            if authentication.challenged(response.status(), response.headers()) {
+                response = self.send(control_url, soap_action, body)?;
+            }
+        }
+
+        let error = response.error_for_status_ref().err();
+        Ok((error, response.text()?))
+    }
+
+    fn send(&self, control_url: &str, soap_action: &str, body: &str) -> Result<reqwest::blocking::Response, reqwest::Error> {
+        let mut request = self
+            .http
+            .post(format!("{}{}", self.base_url, control_url))
+            .header("Content-Type", "text/xml; charset=\"utf-8\"")
+            .header("SOAPACTION", soap_action)
+            .body(body.to_string());
+        if let Some(authorization) = self.authentication.as_ref().and_then(|authentication| authentication.authorization(control_url)) {
+            request = request.header(reqwest::header::AUTHORIZATION, authorization);
+        }
+
+        request.send()
+    }
+}
+
+/// Calls the actions of a FRITZ!Box with async requests. The request files add a method per service,
+/// e.g. `client.tr064_device_info().get_info().await`.
+#[derive(Debug, Clone)]
+pub struct AsyncFritzClient {
+    base_url: String,
+    authentication: Option<Arc<Authentication>>,
+    soap_authentication: Option<Arc<SoapAuthentication>>,
+    rights: Option<Vec<AccessRight>>,
+    http: reqwest::Client,
+}
+
+impl AsyncFritzClient {
+    /// Creates a client for the FRITZ!Box at `base_url`, e.g. `http://fritz.box:49000`.
+    pub fn new(base_url: &str) -> Self {
+        AsyncFritzClient {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            authentication: None,
+            soap_authentication: None,
+            rights: None,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Authenticates as `username` with HTTP digest auth. Clones of the client share the nonce.
+    pub fn with_credentials(mut self, username: &str, password: &str) -> Self {
+        self.authentication = Some(Arc::new(Authentication::new(username, password)));
+        self
+    }
+
+    /// Authenticates as `username` in the SOAP header with `InitChallenge` and `ClientAuth` instead of HTTP digest auth.
+    /// Clones of the client share the nonce.
+    pub fn with_soap_credentials(mut self, username: &str, password: &str) -> Self {
+        self.soap_authentication = Some(Arc::new(SoapAuthentication::new(username, password)));
+        self
+    }
+
+    /// Refuses to call actions needing a right not among `rights`, the rights of the user of `with_credentials`.
+    /// Actions the access rights the files were generated with don't list are always called.
+    pub fn with_rights(mut self, rights: &[AccessRight]) -> Self {
+        self.rights = Some(rights.to_vec());
+        self
+    }
+
+    /// Accepts only the certificates with one of the `fingerprints` over HTTPS, e.g. from `https://fritz.box:49443`.
+    /// The FRITZ!Box returns its HTTPS port with `GetSecurityPort` of `DeviceInfo`.
+    pub fn with_pinned_certificates(mut self, fingerprints: &[Fingerprint]) -> Result<Self, reqwest::Error> {
+        self.http = reqwest::Client::builder()
+            .use_preconfigured_tls(tls_config(fingerprints))
+            .build()?;
+        Ok(self)
+    }
+
+    /// Calls `action` and returns its response.
+    pub async fn call<A: SoapAction>(&self, action: &A) -> Result<A::Response, ClientError> {
+        check_access::<A>(self.rights.as_deref())?;
+        let soap_action = action.soap_action_header();
+        let header = self.soap_authentication.as_ref().map(|authentication| authentication.header());
+        let (mut error, mut body) = self
+            .exchange(A::CONTROL_URL, &soap_action, &action.body_with_header(header.as_ref()))
+            .await.map_err(CallError::Transport)?;
+        if let Some(authentication) = &self.soap_authentication {
+            if authentication.challenged(&body) {
+                // the nonce was missing or expired, the response carries a new one
+                let header = authentication.header();
+                (error, body) = self
+                    .exchange(A::CONTROL_URL, &soap_action, &action.body_with_header(Some(&header)))
+                    .await.map_err(CallError::Transport)?;
+                authentication.challenged(&body);
+            }
+        }
+
+        match error {
+            Some(error) => Err(Fault::of(&body).map_or(CallError::Transport(error), CallError::Fault)),
+            None => soap::response::<A>(&body).map_err(CallError::Deserialize),
+        }
+    }
+
+    /// Posts `body` to `control_url`, answering an HTTP digest challenge. Returns the error of an error status next to
+    /// the response body, which may carry a new SOAP challenge.
+    async fn exchange(&self, control_url: &str, soap_action: &str, body: &str) -> Result<(Option<reqwest::Error>, String), reqwest::Error> {
+        let mut response = self.send(control_url, soap_action, body).await?;
+        if let Some(authentication) = &self.authentication {
+            if authentication.challenged(response.status(), response.headers()) {
+                response = self.send(control_url, soap_action, body).await?;
+            }
+        }
+
+        let error = response.error_for_status_ref().err();
+        Ok((error, response.text().await?))
+    }
+
+    async fn send(&self, control_url: &str, soap_action: &str, body: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self
+            .http
+            .post(format!("{}{}", self.base_url, control_url))
+            .header("Content-Type", "text/xml; charset=\"utf-8\"")
+            .header("SOAPACTION", soap_action)
+            .body(body.to_string());
+        if let Some(authorization) = self.authentication.as_ref().and_then(|authentication| authentication.authorization(control_url)) {
+            request = request.header(reqwest::header::AUTHORIZATION, authorization);
+        }
+
+        request.send().await
+    }
+}
+
+/// Refuses `A` if it needs a right not among `rights`, unless `rights` are unknown.
+fn check_access<A: SoapAction>(rights: Option<&[AccessRight]>) -> Result<(), ClientError> {
+    match (A::ACCESS_RIGHT, rights) {
+        (Some(right), Some(rights)) if right != AccessRight::Anonymous && !rights.contains(&right) => {
+            Err(CallError::AccessDenied {
+                action: A::ACTION_NAME,
+                right,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Credentials and the last HTTP digest challenge of the FRITZ!Box.
+#[derive(Debug)]
+struct Authentication {
+    username: String,
+    password: String,
+    challenge: Mutex<Option<DigestChallenge>>,
+}
+
+/// A `WWW-Authenticate: Digest ..` challenge, its nonce is reused until the FRITZ!Box sends a new one.
+#[derive(Debug)]
+struct DigestChallenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    /// Whether `qop="auth"` was offered, the response then covers a client nonce and the nonce count.
+    qop_auth: bool,
+    nonce_count: u32,
+}
+
+impl Authentication {
+    fn new(username: &str, password: &str) -> Self {
+        Authentication {
+            username: username.to_string(),
+            password: password.to_string(),
+            challenge: Mutex::new(None),
+        }
+    }
+
+    /// Returns the `Authorization` header of a POST to `uri`, `None` until the first challenge arrived.
+    fn authorization(&self, uri: &str) -> Option<String> {
+        let mut challenge = self.challenge.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let challenge = challenge.as_mut()?;
+        challenge.nonce_count += 1;
+
+        let ha1 = md5_hex(&format!("{}:{}:{}", self.username, challenge.realm, self.password));
+        let ha2 = md5_hex(&format!("POST:{}", uri));
+        let mut authorization = format!(
+            "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", algorithm=MD5",
+            self.username, challenge.realm, challenge.nonce, uri
+        );
+        let response = if challenge.qop_auth {
+            let nonce_count = format!("{:08x}", challenge.nonce_count);
+            let cnonce = client_nonce(&challenge.nonce, challenge.nonce_count);
+            authorization.push_str(&format!(", qop=auth, nc={}, cnonce=\"{}\"", nonce_count, cnonce));
+            md5_hex(&format!("{}:{}:{}:{}:auth:{}", ha1, challenge.nonce, nonce_count, cnonce, ha2))
+        } else {
+            md5_hex(&format!("{}:{}:{}", ha1, challenge.nonce, ha2))
+        };
+        authorization.push_str(&format!(", response=\"{}\"", response));
+        if let Some(opaque) = &challenge.opaque {
+            authorization.push_str(&format!(", opaque=\"{}\"", opaque));
+        }
+
+        Some(authorization)
+    }
+
+    /// Remembers the digest challenge of an unauthorized response. Returns whether the request should be repeated,
+    /// which is the case if the challenge isn't the one the request was authorized with.
+    fn challenged(&self, status: reqwest::StatusCode, headers: &reqwest::header::HeaderMap) -> bool {
+        if status != reqwest::StatusCode::UNAUTHORIZED {
+            return false;
+        }
+        let new_challenge = headers
+            .get_all(reqwest::header::WWW_AUTHENTICATE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .find_map(DigestChallenge::parse);
+        let new_challenge = match new_challenge {
+            Some(new_challenge) => new_challenge,
+            None => return false,
+        };
+
+        let mut challenge = self.challenge.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        // the same nonce again means the credentials are wrong, a new one that the old nonce expired
+        let repeat = challenge.as_ref().is_none_or(|challenge| challenge.nonce != new_challenge.nonce);
+        *challenge = Some(new_challenge);
+        repeat
+    }
+}
+
+impl DigestChallenge {
+    /// Parses a `WWW-Authenticate` header value, `None` unless it's a digest challenge.
+    fn parse(value: &str) -> Option<DigestChallenge> {
+        let (scheme, parameters) = value.trim().split_once(' ')?;
+        if !scheme.eq_ignore_ascii_case("digest") {
+            return None;
+        }
+
+        let mut realm = None;
+        let mut nonce = None;
+        let mut opaque = None;
+        let mut qop_auth = false;
+        let mut rest = parameters.trim_start();
+        while let Some((name, after_name)) = rest.split_once('=') {
+            let after_name = after_name.trim_start();
+            let (value, after_value) = match after_name.strip_prefix('"') {
+                Some(quoted) => {
+                    let end = quoted.find('"')?;
+                    (&quoted[..end], &quoted[end + 1..])
+                }
+                None => {
+                    let end = after_name.find(',').unwrap_or(after_name.len());
+                    (after_name[..end].trim_end(), &after_name[end..])
+                }
+            };
+            match name.trim().to_ascii_lowercase().as_str() {
+                "realm" => realm = Some(value.to_string()),
+                "nonce" => nonce = Some(value.to_string()),
+                "opaque" => opaque = Some(value.to_string()),
+                "qop" => qop_auth = value.split(',').any(|qop| qop.trim().eq_ignore_ascii_case("auth")),
+                _ => {}
+            }
+            rest = after_value.trim_start().trim_start_matches(',').trim_start();
+        }
+
+        Some(DigestChallenge {
+            realm: realm?,
+            nonce: nonce?,
+            opaque,
+            qop_auth,
+            nonce_count: 0,
+        })
+    }
+}
+
+/// Credentials and the last nonce of the authentication in the SOAP header.
+#[derive(Debug)]
+struct SoapAuthentication {
+    username: String,
+    password: String,
+    challenge: Mutex<Option<SoapChallenge>>,
+}
+
+impl SoapAuthentication {
+    fn new(username: &str, password: &str) -> Self {
+        SoapAuthentication {
+            username: username.to_string(),
+            password: password.to_string(),
+            challenge: Mutex::new(None),
+        }
+    }
+
+    /// Returns the SOAP header of the next request, `ClientAuth` answering the last challenge or `InitChallenge`.
+    fn header(&self) -> SoapHeader {
+        let challenge = self.challenge.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match challenge.as_ref() {
+            Some(challenge) => {
+                let secret = md5_hex(&format!("{}:{}:{}", self.username, challenge.realm, self.password));
+                SoapHeader::ClientAuth {
+                    nonce: challenge.nonce.clone(),
+                    auth: md5_hex(&format!("{}:{}", secret, challenge.nonce)),
+                    user_id: self.username.clone(),
+                    realm: challenge.realm.clone(),
+                }
+            }
+            None => SoapHeader::InitChallenge {
+                user_id: self.username.clone(),
+            },
+        }
+    }
+
+    /// Remembers the challenge in the response envelope `body`. Returns whether the request was refused and has to be
+    /// repeated with the new nonce.
+    fn challenged(&self, body: &str) -> bool {
+        let new_challenge = match SoapChallenge::of(body) {
+            Some(new_challenge) => new_challenge,
+            None => return false,
+        };
+        let refused = new_challenge.is_unauthenticated();
+        *self.challenge.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(new_challenge);
+        refused
+    }
+}
+
+fn md5_hex(value: &str) -> String {
+    format!("{:x}", md5::compute(value))
+}
+
+/// Returns a client nonce that differs per request.
+fn client_nonce(nonce: &str, nonce_count: u32) -> String {
+    let time = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    md5_hex(&format!("{}:{}:{}", nonce, nonce_count, time))[..16].to_string()
+}
+
+/// SHA-256 fingerprint of a certificate the clients accept over HTTPS, parsed from hex digits optionally separated by
+/// colons like `openssl x509 -fingerprint -sha256` prints them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fingerprint([u8; 32]);
+
+impl Fingerprint {
+    /// Fingerprint of the DER encoded `certificate`.
+    pub fn of(certificate: &[u8]) -> Self {
+        Fingerprint(sha2::Sha256::digest(certificate).into())
+    }
+
+    /// Fingerprints of the certificates in the PEM file content `pem`, e.g. the certificate exported from the
+    /// FRITZ!Box. Fails if it doesn't contain any.
+    pub fn of_pem(mut pem: &[u8]) -> std::io::Result<Vec<Fingerprint>> {
+        let fingerprints = rustls_pemfile::certs(&mut pem)
+            .map(|certificate| certificate.map(|certificate| Fingerprint::of(&certificate)))
+            .collect::<std::io::Result<Vec<Fingerprint>>>()?;
+        if fingerprints.is_empty() {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "no certificate found"));
+        }
+
+        Ok(fingerprints)
+    }
+}
+
+impl std::str::FromStr for Fingerprint {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let digits: Vec<u8> = input.bytes().filter(|digit| *digit != b':').collect();
+        let mut fingerprint = [0; 32];
+        if digits.len() != fingerprint.len() * 2 {
+            return Err(format!("expected a SHA-256 fingerprint of 64 hex digits, got `{}`", input));
+        }
+        for (byte, pair) in fingerprint.iter_mut().zip(digits.chunks(2)) {
+            let pair = std::str::from_utf8(pair).map_err(|e| e.to_string())?;
+            *byte = u8::from_str_radix(pair, 16).map_err(|_| format!("invalid hex digits `{}` in `{}`", pair, input))?;
+        }
+
+        Ok(Fingerprint(fingerprint))
+    }
+}
+
+fn tls_config(fingerprints: &[Fingerprint]) -> rustls::ClientConfig {
+    let provider = rustls::crypto::ring::default_provider();
+    let algorithms = provider.signature_verification_algorithms;
+    rustls::ClientConfig::builder_with_provider(Arc::new(provider))
+        .with_safe_default_protocol_versions()
+        .expect("ring supports the default protocol versions")
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(PinnedVerifier {
+            fingerprints: fingerprints.to_vec(),
+            algorithms,
+        }))
+        .with_no_client_auth()
+}
+
+/// Accepts the pinned certificates instead of verifying a chain and the host name, the FRITZ!Box signs its own.
+#[derive(Debug)]
+struct PinnedVerifier {
+    fingerprints: Vec<Fingerprint>,
+    algorithms: rustls::crypto::WebPkiSupportedAlgorithms,
+}
+
+impl ServerCertVerifier for PinnedVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if self.fingerprints.contains(&Fingerprint::of(end_entity)) {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::InvalidCertificate(rustls::CertificateError::ApplicationVerificationFailure))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
+
+}
+pub mod igd_requests {
+pub mod igd_any {
+use super::super::soap::SoapAction;
+pub fn generate_get_uptime_request() -> (String, String, String) {
+
+    let request = GetUptimeRequest;
+
+    (GetUptimeRequest::CONTROL_URL.to_string(), request.soap_action_header(), request.body())
+}
+
+/// Arguments of the action `GetUptime`.
+#[derive(Debug)]
+pub struct GetUptimeRequest;
+
+impl SoapAction for GetUptimeRequest {
+    type Response = super::super::igd_responses::igd_any::GetUptimeResponse;
+
+    const CONTROL_URL: &'static str = "/igdupnp/control/any";
+    const SERVICE_TYPE: &'static str = "urn:schemas-any-com:service:Any:1";
+    const ACTION_NAME: &'static str = "GetUptime";
+
+    fn body_with_header(&self, header: Option<&super::super::soap::SoapHeader>) -> String {
+        super::igd_multi_use::envelope_with_header(header, Self::SERVICE_TYPE, Self::ACTION_NAME, &[])
+    }
+}
+
+/// Calls the actions of the service through a `FritzClient`.
+pub struct Client<'c> {
+    client: &'c super::super::client::FritzClient,
+}
+
+impl super::super::client::FritzClient {
+    /// Calls the actions of the service of `igd_any`.
+    pub fn igd_any(&self) -> Client<'_> {
+        Client { client: self }
+    }
+}
+
+impl Client<'_> {
+    /// Calls `GetUptime`.
+    pub fn get_uptime(&self) -> Result<super::super::igd_responses::igd_any::GetUptimeResponse, super::super::client::ClientError> {
+        let request = GetUptimeRequest;
+        self.client.call(&request)
+    }
+}
+/// Calls the actions of the service through an `AsyncFritzClient`.
+pub struct AsyncClient<'c> {
+    client: &'c super::super::client::AsyncFritzClient,
+}
+
+impl super::super::client::AsyncFritzClient {
+    /// Calls the actions of the service of `igd_any`.
+    pub fn igd_any(&self) -> AsyncClient<'_> {
+        AsyncClient { client: self }
+    }
+}
+
+impl AsyncClient<'_> {
+    /// Calls `GetUptime`.
+    pub async fn get_uptime(&self) -> Result<super::super::igd_responses::igd_any::GetUptimeResponse, super::super::client::ClientError> {
+        let request = GetUptimeRequest;
+        self.client.call(&request).await
+    }
+}
+
+}
+pub mod igd_multi_use {
+/// Escapes `value` for XML text and attribute values.
+/// `\r` is escaped as well, a parser would normalize it to `\n` otherwise.
+/// Characters XML 1.0 can't represent, not even escaped, are replaced with `U+FFFD`: the control characters other than
+/// tab, line feed and carriage return, `U+FFFE` and `U+FFFF`.
+pub fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\t' | '\n' => escaped.push(c),
+            '\u{0}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}' => escaped.push(char::REPLACEMENT_CHARACTER),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Builds the SOAP envelope calling `action` of `service_type` with `arguments`, every value is escaped.
+pub fn envelope(service_type: &str, action: &str, arguments: &[(&str, String)]) -> String {
+    envelope_with_header(None, service_type, action, arguments)
+}
+
+/// Builds the SOAP envelope like `envelope`, with `header` as SOAP header.
+pub fn envelope_with_header(
+    header: Option<&super::super::soap::SoapHeader>,
+    service_type: &str,
+    action: &str,
+    arguments: &[(&str, String)],
+) -> String {
+    let mut body = String::from(concat!(
+        "<?xml version=\"1.0\"?>\n",
+        "<s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" ",
+        "s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\n",
+    ));
+    if let Some(header) = header {
+        body.push_str(&soap_header(header));
+    }
+    body.push_str("<s:Body>\n");
+    body.push_str(&format!("<u:{} xmlns:u=\"{}\">\n", action, escape_xml(service_type)));
+    for (name, value) in arguments {
+        body.push_str(&format!("<{}>{}</{}>\n", name, escape_xml(value), name));
+    }
+    body.push_str(&format!("</u:{}>\n</s:Body>\n</s:Envelope>\n", action));
+
+    body
+}
+
+/// Builds the `s:Header` element carrying `header`.
+fn soap_header(header: &super::super::soap::SoapHeader) -> String {
+    use super::super::soap::{SoapHeader, AUTHENTICATION_NAMESPACE};
+
+    let (element, values) = match header {
+        SoapHeader::InitChallenge { user_id } => ("InitChallenge", vec![("UserID", user_id)]),
+        SoapHeader::ClientAuth { nonce, auth, user_id, realm } => (
+            "ClientAuth",
+            vec![("Nonce", nonce), ("Auth", auth), ("UserID", user_id), ("Realm", realm)],
+        ),
+    };
+    let mut header = format!(
+        "<s:Header>\n<h:{} xmlns:h=\"{}\" s:mustUnderstand=\"1\">\n",
+        element, AUTHENTICATION_NAMESPACE
+    );
+    for (name, value) in values {
+        header.push_str(&format!("<{}>{}</{}>\n", name, escape_xml(value), name));
+    }
+    header.push_str(&format!("</h:{}>\n</s:Header>\n", element));
+
+    header
+}
+
+}
+}
+pub mod igd_responses {
+pub mod igd_any {
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct GetUptimeResponse {
+    #[serde(rename = "NewUptime")]
+    pub new_uptime: u32,
+}
+
+
+
+}
+pub mod igd_multi_use {
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct Envelope<T> {
+    #[serde(rename = "Body")]
+    pub body: Body<T>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Body<T> {
+    #[serde(
+        alias = "GetUptimeResponse",
+    )]
+    pub response: T,
+}
+
+/// An envelope holding either the response `T` or the `Fault` the FRITZ!Box answered a failed action with.
+#[derive(Deserialize, Debug)]
+pub struct ResultEnvelope<T> {
+    #[serde(rename = "Body")]
+    pub body: ResultBody<T>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResultBody<T> {
+    #[serde(rename = "Fault")]
+    pub fault: Option<super::super::soap::Fault>,
+    #[serde(
+        alias = "GetUptimeResponse",
+    )]
+    pub response: Option<T>,
+}
+
+impl<T> ResultEnvelope<T> {
+    /// Returns the response or the fault, `None` if the body holds neither.
+    pub fn into_result(self) -> Option<Result<T, super::super::soap::Fault>> {
+        match self.body.fault {
+            Some(fault) => Some(Err(fault)),
+            None => self.body.response.map(Ok),
+        }
+    }
+}
+}
+}
+pub mod soap {
+use serde::Deserialize;
+
+/// An action of a service, implemented by the request structs of the requests folders.
+pub trait SoapAction {
+    /// The struct the response deserializes into, wrapped in the `Envelope` of the responses folder.
+    type Response: serde::de::DeserializeOwned;
+
+    const CONTROL_URL: &'static str;
+    const SERVICE_TYPE: &'static str;
+    const ACTION_NAME: &'static str;
+    /// The right needed to call the action, `None` if the access rights the files were generated with don't list it.
+    const ACCESS_RIGHT: Option<AccessRight> = None;
+
+    /// Returns the SOAP envelope calling the action with the arguments of `self` and `header` as SOAP header.
+    fn body_with_header(&self, header: Option<&SoapHeader>) -> String;
+
+    /// Returns the SOAP envelope calling the action with the arguments of `self`.
+    fn body(&self) -> String {
+        self.body_with_header(None)
+    }
+
+    /// Returns the value of the `SOAPAction` header, `SERVICE_TYPE#ACTION_NAME`.
+    fn soap_action_header(&self) -> String {
+        format!("{}#{}", Self::SERVICE_TYPE, Self::ACTION_NAME)
+    }
+}
+
+/// Right a FRITZ!Box user needs to call an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessRight {
+    /// Callable without login.
+    Anonymous,
+    /// Access from FRITZ!Apps.
+    App,
+    /// FRITZ!Box settings.
+    Settings,
+    /// Voice messages, fax messages, FRITZ!App Fon and call list.
+    Phone,
+    /// Access to NAS contents.
+    Nas,
+    /// Smart Home.
+    SmartHome,
+}
+
+/// Namespace of the authentication in the SOAP header defined by TR-064.
+pub const AUTHENTICATION_NAMESPACE: &str = "http://soap-authentication.org/digest/2001/10/";
+
+/// Authentication in the SOAP header of a request, the alternative to HTTP digest auth defined by TR-064.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SoapHeader {
+    /// Asks for a nonce, answered with a `SoapChallenge`.
+    InitChallenge { user_id: String },
+    /// Answers the last `SoapChallenge`, `auth` is `md5(md5(user_id:realm:password):nonce)` as lowercase hex.
+    ClientAuth {
+        nonce: String,
+        auth: String,
+        user_id: String,
+        realm: String,
+    },
+}
+
+/// The `Challenge` or `NextChallenge` in the SOAP header of a response, its nonce authenticates the next request.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SoapChallenge {
+    /// `Unauthenticated` if the request was refused, `Authenticated` otherwise.
+    #[serde(rename = "Status", default)]
+    pub status: String,
+    #[serde(rename = "Nonce")]
+    pub nonce: String,
+    #[serde(rename = "Realm")]
+    pub realm: String,
+}
+
+impl SoapChallenge {
+    /// Returns the challenge in the SOAP header of the response envelope `body`, if there is one.
+    pub fn of(body: &str) -> Option<SoapChallenge> {
+        let envelope: HeaderEnvelope = serde_xml_rs::from_str(body).ok()?;
+        let header = envelope.header?;
+        header.challenge.or(header.next_challenge)
+    }
+
+    /// Whether the request was refused, it has to be repeated answering this challenge.
+    pub fn is_unauthenticated(&self) -> bool {
+        self.status == "Unauthenticated"
+    }
+}
+
+/// The `s:Fault` a device answers a failed action with instead of the response.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Fault {
+    /// `s:Client` if the request was at fault, `s:Server` otherwise.
+    #[serde(rename = "faultcode")]
+    pub fault_code: String,
+    /// `UPnPError` if `detail` holds one.
+    #[serde(rename = "faultstring")]
+    pub fault_string: String,
+    pub detail: Option<FaultDetail>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FaultDetail {
+    #[serde(rename = "UPnPError")]
+    pub upnp_error: Option<UpnpError>,
+}
+
+/// Why an action failed, `error_code` is one of `ErrorCode`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct UpnpError {
+    #[serde(rename = "errorCode")]
+    pub error_code: u16,
+    #[serde(rename = "errorDescription", default)]
+    pub error_description: String,
+}
+
+impl Fault {
+    /// Returns the fault in the envelope `body`, if the device answered with one.
+    pub fn of(body: &str) -> Option<Fault> {
+        let envelope: FaultEnvelope = serde_xml_rs::from_str(body).ok()?;
+        envelope.body.fault
+    }
+
+    pub fn upnp_error(&self) -> Option<&UpnpError> {
+        self.detail.as_ref()?.upnp_error.as_ref()
+    }
+
+    /// The code of the `UPnPError`, `None` if the fault doesn't have one.
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        self.upnp_error().map(|upnp_error| ErrorCode::from(upnp_error.error_code))
+    }
+}
+
+impl std::fmt::Display for Fault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.upnp_error() {
+            Some(upnp_error) => write!(
+                f,
+                "{} {} ({:?}): {}",
+                self.fault_string,
+                upnp_error.error_code,
+                ErrorCode::from(upnp_error.error_code),
+                upnp_error.error_description
+            ),
+            None => write!(f, "{}: {}", self.fault_code, self.fault_string),
+        }
+    }
+}
+
+impl std::error::Error for Fault {}
+
+/// The codes of a `UPnPError`, the standard UPnP ones and those AVM added for TR-064.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// 401, the service has no action of that name.
+    InvalidAction,
+    /// 402, arguments are missing, in the wrong order or of the wrong type.
+    InvalidArgs,
+    /// 501, the device failed to complete the action.
+    ActionFailed,
+    /// 600, an argument value isn't valid.
+    ArgumentValueInvalid,
+    /// 601, an argument value is outside of the allowed range.
+    ArgumentValueOutOfRange,
+    /// 602, the optional action isn't implemented.
+    OptionalActionNotImplemented,
+    /// 603, the device doesn't have enough memory to complete the action.
+    OutOfMemory,
+    /// 604, the action needs human intervention.
+    HumanInterventionRequired,
+    /// 605, a string argument is too long.
+    StringArgumentTooLong,
+    /// 606, the user isn't allowed to call the action, see `AccessRight`.
+    ActionNotAuthorized,
+    /// 713, AVM: the index of an array entry is out of range.
+    SpecifiedArrayIndexInvalid,
+    /// 714, AVM: no array entry matches the arguments.
+    NoSuchEntryInArray,
+    /// 820, AVM: the FRITZ!Box failed internally.
+    InternalError,
+    /// 866, AVM: the action needs a second factor, e.g. pressing a button on the FRITZ!Box.
+    SecondFactorAuthenticationRequired,
+    /// 867, AVM: the second factor authentication is blocked for a while.
+    SecondFactorAuthenticationBlocked,
+    /// 868, AVM: another second factor authentication is in progress.
+    SecondFactorAuthenticationBusy,
+    /// Any other code.
+    Other(u16),
+}
+
+impl ErrorCode {
+    /// The numeric code, e.g. `401`.
+    pub fn code(&self) -> u16 {
+        match self {
+            ErrorCode::InvalidAction => 401,
+            ErrorCode::InvalidArgs => 402,
+            ErrorCode::ActionFailed => 501,
+            ErrorCode::ArgumentValueInvalid => 600,
+            ErrorCode::ArgumentValueOutOfRange => 601,
+            ErrorCode::OptionalActionNotImplemented => 602,
+            ErrorCode::OutOfMemory => 603,
+            ErrorCode::HumanInterventionRequired => 604,
+            ErrorCode::StringArgumentTooLong => 605,
+            ErrorCode::ActionNotAuthorized => 606,
+            ErrorCode::SpecifiedArrayIndexInvalid => 713,
+            ErrorCode::NoSuchEntryInArray => 714,
+            ErrorCode::InternalError => 820,
+            ErrorCode::SecondFactorAuthenticationRequired => 866,
+            ErrorCode::SecondFactorAuthenticationBlocked => 867,
+            ErrorCode::SecondFactorAuthenticationBusy => 868,
+            ErrorCode::Other(code) => *code,
+        }
+    }
+}
+
+impl From<u16> for ErrorCode {
+    fn from(code: u16) -> Self {
+        match code {
+            401 => ErrorCode::InvalidAction,
+            402 => ErrorCode::InvalidArgs,
+            501 => ErrorCode::ActionFailed,
+            600 => ErrorCode::ArgumentValueInvalid,
+            601 => ErrorCode::ArgumentValueOutOfRange,
+            602 => ErrorCode::OptionalActionNotImplemented,
+            603 => ErrorCode::OutOfMemory,
+            604 => ErrorCode::HumanInterventionRequired,
+            605 => ErrorCode::StringArgumentTooLong,
+            606 => ErrorCode::ActionNotAuthorized,
+            713 => ErrorCode::SpecifiedArrayIndexInvalid,
+            714 => ErrorCode::NoSuchEntryInArray,
+            820 => ErrorCode::InternalError,
+            866 => ErrorCode::SecondFactorAuthenticationRequired,
+            867 => ErrorCode::SecondFactorAuthenticationBlocked,
+            868 => ErrorCode::SecondFactorAuthenticationBusy,
+            code => ErrorCode::Other(code),
+        }
+    }
+}
+
+/// Sends request bodies to the device, e.g. with an HTTP client.
+pub trait Transport {
+    type Error;
+
+    /// Posts `body` to `control_url` with `soap_action` as `SOAPAction` header and returns the response body. The body
+    /// of an error status is returned too, `call` reads the `Fault` out of it.
+    fn post(&mut self, control_url: &str, soap_action: &str, body: &str) -> Result<String, Self::Error>;
+}
+
+/// Everything that can go wrong in `call`.
+#[derive(Debug)]
+pub enum CallError<E> {
+    /// The transport couldn't send the request or receive the response.
+    Transport(E),
+    /// The response isn't an envelope containing the response of the action.
+    Deserialize(serde_xml_rs::Error),
+    /// The device answered with a fault, e.g. `ErrorCode::ActionNotAuthorized`.
+    Fault(Fault),
+    /// The action needs `right`, which the user of the client doesn't have. Nothing was sent.
+    AccessDenied {
+        action: &'static str,
+        right: AccessRight,
+    },
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for CallError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CallError::Transport(source) => write!(f, "failed to send request: {}", source),
+            CallError::Deserialize(source) => write!(f, "failed to deserialize response: {}", source),
+            CallError::Fault(fault) => write!(f, "action failed: {}", fault),
+            CallError::AccessDenied { action, right } => write!(f, "{} needs the access right {:?}", action, right),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for CallError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CallError::Transport(source) => Some(source),
+            CallError::Deserialize(source) => Some(source),
+            CallError::Fault(fault) => Some(fault),
+            CallError::AccessDenied { .. } => None,
+        }
+    }
+}
+
+/// Calls `action` through `transport` and returns its typed response.
+pub fn call<A: SoapAction, T: Transport>(transport: &mut T, action: &A) -> Result<A::Response, CallError<T::Error>> {
+    let response = transport
+        .post(A::CONTROL_URL, &action.soap_action_header(), &action.body())
+        .map_err(CallError::Transport)?;
+    if let Some(fault) = Fault::of(&response) {
+        return Err(CallError::Fault(fault));
+    }
+    self::response::<A>(&response).map_err(CallError::Deserialize)
+}
+
+/// Deserializes the response of the action `A` out of the envelope `body`.
+pub fn response<A: SoapAction>(body: &str) -> Result<A::Response, serde_xml_rs::Error> {
+    let envelope: Envelope<A::Response> = serde_xml_rs::from_str(body)?;
+    Ok(envelope.body.response)
+}
+
+#[derive(Deserialize)]
+struct Envelope<T> {
+    #[serde(rename = "Body")]
+    body: Body<T>,
+}
+
+#[derive(Deserialize)]
+struct Body<T> {
+    // the element is named after the action, e.g. `u:GetInfoResponse`
+    #[serde(rename = "$value")]
+    response: T,
+}
+
+#[derive(Deserialize)]
+struct HeaderEnvelope {
+    #[serde(rename = "Header")]
+    header: Option<Header>,
+}
+
+#[derive(Deserialize)]
+struct Header {
+    #[serde(rename = "Challenge")]
+    challenge: Option<SoapChallenge>,
+    #[serde(rename = "NextChallenge")]
+    next_challenge: Option<SoapChallenge>,
+}
+
+#[derive(Deserialize)]
+struct FaultEnvelope {
+    #[serde(rename = "Body")]
+    body: FaultBody,
+}
+
+#[derive(Deserialize)]
+struct FaultBody {
+    #[serde(rename = "Fault")]
+    fault: Option<Fault>,
+}
+
+}
+pub mod tr064_requests {
+pub mod tr064_device_config {
+use super::super::soap::SoapAction;
+pub fn generate_get_persistent_data_request() -> (String, String, String) {
+
+    let request = GetPersistentDataRequest;
+
+    (GetPersistentDataRequest::CONTROL_URL.to_string(), request.soap_action_header(), request.body())
+}
+
+/// Arguments of the action `GetPersistentData`.
+#[derive(Debug)]
+pub struct GetPersistentDataRequest;
+
+impl SoapAction for GetPersistentDataRequest {
+    type Response = super::super::tr064_responses::tr064_device_config::GetPersistentDataResponse;
+
+    const CONTROL_URL: &'static str = "/upnp/control/deviceconfig";
+    const SERVICE_TYPE: &'static str = "urn:dslforum-org:service:DeviceConfig:1";
+    const ACTION_NAME: &'static str = "GetPersistentData";
+
+    fn body_with_header(&self, header: Option<&super::super::soap::SoapHeader>) -> String {
+        super::tr064_multi_use::envelope_with_header(header, Self::SERVICE_TYPE, Self::ACTION_NAME, &[])
+    }
+}
+
+pub fn generate_set_persistent_data_request(new_persistent_data: &str) -> (String, String, String) {
+
+    let request = SetPersistentDataRequest {
+        new_persistent_data: new_persistent_data.to_string(),
+    };
+
+    (SetPersistentDataRequest::CONTROL_URL.to_string(), request.soap_action_header(), request.body())
+}
+
+/// Arguments of the action `SetPersistentData`.
+#[derive(Debug)]
+pub struct SetPersistentDataRequest {
+    pub new_persistent_data: String,
+}
+
+impl SoapAction for SetPersistentDataRequest {
+    type Response = super::super::tr064_responses::tr064_device_config::SetPersistentDataResponse;
+
+    const CONTROL_URL: &'static str = "/upnp/control/deviceconfig";
+    const SERVICE_TYPE: &'static str = "urn:dslforum-org:service:DeviceConfig:1";
+    const ACTION_NAME: &'static str = "SetPersistentData";
+
+    fn body_with_header(&self, header: Option<&super::super::soap::SoapHeader>) -> String {
+        let SetPersistentDataRequest {
+            new_persistent_data,
+        } = self;
+        super::tr064_multi_use::envelope_with_header(
+            header,
+            Self::SERVICE_TYPE,
+            Self::ACTION_NAME,
+            &[
+                ("NewPersistentData", new_persistent_data.to_string()),
+            ],
+        )
+    }
+}
+
+/// Needs the access right `Settings`.
+pub fn generate_reboot_request() -> (String, String, String) {
+
+    let request = RebootRequest;
+
+    (RebootRequest::CONTROL_URL.to_string(), request.soap_action_header(), request.body())
+}
+
+/// Arguments of the action `Reboot`.
+///
+/// Needs the access right `Settings`.
+#[derive(Debug)]
+pub struct RebootRequest;
+
+impl SoapAction for RebootRequest {
+    type Response = super::super::tr064_responses::tr064_device_config::RebootResponse;
+
+    const CONTROL_URL: &'static str = "/upnp/control/deviceconfig";
+    const SERVICE_TYPE: &'static str = "urn:dslforum-org:service:DeviceConfig:1";
+    const ACTION_NAME: &'static str = "Reboot";
+    const ACCESS_RIGHT: Option<super::super::soap::AccessRight> = Some(super::super::soap::AccessRight::Settings);
+
+    fn body_with_header(&self, header: Option<&super::super::soap::SoapHeader>) -> String {
+        super::tr064_multi_use::envelope_with_header(header, Self::SERVICE_TYPE, Self::ACTION_NAME, &[])
+    }
+}
+
+pub fn generate_x_avm_de_create_url_sid_request() -> (String, String, String) {
+
+    let request = XAVMDECreateUrlSIDRequest;
+
+    (XAVMDECreateUrlSIDRequest::CONTROL_URL.to_string(), request.soap_action_header(), request.body())
+}
+
+/// Arguments of the action `X_AVM-DE_CreateUrlSID`.
+#[derive(Debug)]
+pub struct XAVMDECreateUrlSIDRequest;
+
+impl SoapAction for XAVMDECreateUrlSIDRequest {
+    type Response = super::super::tr064_responses::tr064_device_config::XAVMDECreateUrlSIDResponse;
+
+    const CONTROL_URL: &'static str = "/upnp/control/deviceconfig";
+    const SERVICE_TYPE: &'static str = "urn:dslforum-org:service:DeviceConfig:1";
+    const ACTION_NAME: &'static str = "X_AVM-DE_CreateUrlSID";
+
+    fn body_with_header(&self, header: Option<&super::super::soap::SoapHeader>) -> String {
+        super::tr064_multi_use::envelope_with_header(header, Self::SERVICE_TYPE, Self::ACTION_NAME, &[])
+    }
+}
+
+pub fn generate_x_avm_de_set_config_file_request(new_x_avm_de_password: &str, new_x_avm_de_config_file_url: &str) -> (String, String, String) {
+
+    let request = XAVMDESetConfigFileRequest {
+        new_x_avm_de_password: new_x_avm_de_password.to_string(),
+        new_x_avm_de_config_file_url: new_x_avm_de_config_file_url.to_string(),
+    };
+
+    (XAVMDESetConfigFileRequest::CONTROL_URL.to_string(), request.soap_action_header(), request.body())
+}
+
+/// Arguments of the action `X_AVM-DE_SetConfigFile`.
+#[derive(Debug)]
+pub struct XAVMDESetConfigFileRequest {
+    pub new_x_avm_de_password: String,
+    pub new_x_avm_de_config_file_url: String,
+}
+
+impl SoapAction for XAVMDESetConfigFileRequest {
+    type Response = super::super::tr064_responses::tr064_device_config::XAVMDESetConfigFileResponse;
+
+    const CONTROL_URL: &'static str = "/upnp/control/deviceconfig";
+    const SERVICE_TYPE: &'static str = "urn:dslforum-org:service:DeviceConfig:1";
+    const ACTION_NAME: &'static str = "X_AVM-DE_SetConfigFile";
+
+    fn body_with_header(&self, header: Option<&super::super::soap::SoapHeader>) -> String {
+        let XAVMDESetConfigFileRequest {
+            new_x_avm_de_password,
+            new_x_avm_de_config_file_url,
+        } = self;
+        super::tr064_multi_use::envelope_with_header(
+            header,
+            Self::SERVICE_TYPE,
+            Self::ACTION_NAME,
+            &[
+                ("NewX_AVM-DE_Password", new_x_avm_de_password.to_string()),
+                ("NewX_AVM-DE_ConfigFileUrl", new_x_avm_de_config_file_url.to_string()),
+            ],
+        )
+    }
+}
+
+/// Calls the actions of the service through a `FritzClient`.
+pub struct Client<'c> {
+    client: &'c super::super::client::FritzClient,
+}
+
+impl super::super::client::FritzClient {
+    /// Calls the actions of the service of `tr064_device_config`.
+    pub fn tr064_device_config(&self) -> Client<'_> {
+        Client { client: self }
+    }
+}
+
+impl Client<'_> {
+    /// Calls `GetPersistentData`.
+    pub fn get_persistent_data(&self) -> Result<super::super::tr064_responses::tr064_device_config::GetPersistentDataResponse, super::super::client::ClientError> {
+        let request = GetPersistentDataRequest;
+        self.client.call(&request)
+    }
+
+    /// Calls `SetPersistentData`.
+    pub fn set_persistent_data(&self, new_persistent_data: &str) -> Result<super::super::tr064_responses::tr064_device_config::SetPersistentDataResponse, super::super::client::ClientError> {
+        let request = SetPersistentDataRequest {
+            new_persistent_data: new_persistent_data.to_string(),
+        };
+        self.client.call(&request)
+    }
+
+    /// Calls `Reboot`.
+    ///
+    /// Needs the access right `Settings`.
+    pub fn reboot(&self) -> Result<super::super::tr064_responses::tr064_device_config::RebootResponse, super::super::client::ClientError> {
+        let request = RebootRequest;
+        self.client.call(&request)
+    }
+
+    /// Calls `X_AVM-DE_CreateUrlSID`.
+    pub fn x_avm_de_create_url_sid(&self) -> Result<super::super::tr064_responses::tr064_device_config::XAVMDECreateUrlSIDResponse, super::super::client::ClientError> {
+        let request = XAVMDECreateUrlSIDRequest;
+        self.client.call(&request)
+    }
+
+    /// Calls `X_AVM-DE_SetConfigFile`.
+    pub fn x_avm_de_set_config_file(&self, new_x_avm_de_password: &str, new_x_avm_de_config_file_url: &str) -> Result<super::super::tr064_responses::tr064_device_config::XAVMDESetConfigFileResponse, super::super::client::ClientError> {
+        let request = XAVMDESetConfigFileRequest {
+            new_x_avm_de_password: new_x_avm_de_password.to_string(),
+            new_x_avm_de_config_file_url: new_x_avm_de_config_file_url.to_string(),
+        };
+        self.client.call(&request)
+    }
+}
+/// Calls the actions of the service through an `AsyncFritzClient`.
+pub struct AsyncClient<'c> {
+    client: &'c super::super::client::AsyncFritzClient,
+}
+
+impl super::super::client::AsyncFritzClient {
+    /// Calls the actions of the service of `tr064_device_config`.
+    pub fn tr064_device_config(&self) -> AsyncClient<'_> {
+        AsyncClient { client: self }
+    }
+}
+
+impl AsyncClient<'_> {
+    /// Calls `GetPersistentData`.
+    pub async fn get_persistent_data(&self) -> Result<super::super::tr064_responses::tr064_device_config::GetPersistentDataResponse, super::super::client::ClientError> {
+        let request = GetPersistentDataRequest;
+        self.client.call(&request).await
+    }
+
+    /// Calls `SetPersistentData`.
+    pub async fn set_persistent_data(&self, new_persistent_data: &str) -> Result<super::super::tr064_responses::tr064_device_config::SetPersistentDataResponse, super::super::client::ClientError> {
+        let request = SetPersistentDataRequest {
+            new_persistent_data: new_persistent_data.to_string(),
+        };
+        self.client.call(&request).await
+    }
+
+    /// Calls `Reboot`.
+    ///
+    /// Needs the access right `Settings`.
+    pub async fn reboot(&self) -> Result<super::super::tr064_responses::tr064_device_config::RebootResponse, super::super::client::ClientError> {
+        let request = RebootRequest;
+        self.client.call(&request).await
+    }
+
+    /// Calls `X_AVM-DE_CreateUrlSID`.
+    pub async fn x_avm_de_create_url_sid(&self) -> Result<super::super::tr064_responses::tr064_device_config::XAVMDECreateUrlSIDResponse, super::super::client::ClientError> {
+        let request = XAVMDECreateUrlSIDRequest;
+        self.client.call(&request).await
+    }
+
+    /// Calls `X_AVM-DE_SetConfigFile`.
+    pub async fn x_avm_de_set_config_file(&self, new_x_avm_de_password: &str, new_x_avm_de_config_file_url: &str) -> Result<super::super::tr064_responses::tr064_device_config::XAVMDESetConfigFileResponse, super::super::client::ClientError> {
+        let request = XAVMDESetConfigFileRequest {
+            new_x_avm_de_password: new_x_avm_de_password.to_string(),
+            new_x_avm_de_config_file_url: new_x_avm_de_config_file_url.to_string(),
+        };
+        self.client.call(&request).await
+    }
+}
+
+}
+pub mod tr064_multi_use {
+/// Escapes `value` for XML text and attribute values.
+/// `\r` is escaped as well, a parser would normalize it to `\n` otherwise.
+/// Characters XML 1.0 can't represent, not even escaped, are replaced with `U+FFFD`: the control characters other than
+/// tab, line feed and carriage return, `U+FFFE` and `U+FFFF`.
+pub fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\t' | '\n' => escaped.push(c),
+            '\u{0}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}' => escaped.push(char::REPLACEMENT_CHARACTER),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Builds the SOAP envelope calling `action` of `service_type` with `arguments`, every value is escaped.
+pub fn envelope(service_type: &str, action: &str, arguments: &[(&str, String)]) -> String {
+    envelope_with_header(None, service_type, action, arguments)
+}
+
+/// Builds the SOAP envelope like `envelope`, with `header` as SOAP header.
+pub fn envelope_with_header(
+    header: Option<&super::super::soap::SoapHeader>,
+    service_type: &str,
+    action: &str,
+    arguments: &[(&str, String)],
+) -> String {
+    let mut body = String::from(concat!(
+        "<?xml version=\"1.0\"?>\n",
+        "<s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" ",
+        "s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\n",
+    ));
+    if let Some(header) = header {
+        body.push_str(&soap_header(header));
+    }
+    body.push_str("<s:Body>\n");
+    body.push_str(&format!("<u:{} xmlns:u=\"{}\">\n", action, escape_xml(service_type)));
+    for (name, value) in arguments {
+        body.push_str(&format!("<{}>{}</{}>\n", name, escape_xml(value), name));
+    }
+    body.push_str(&format!("</u:{}>\n</s:Body>\n</s:Envelope>\n", action));
+
+    body
+}
+
+/// Builds the `s:Header` element carrying `header`.
+fn soap_header(header: &super::super::soap::SoapHeader) -> String {
+    use super::super::soap::{SoapHeader, AUTHENTICATION_NAMESPACE};
+
+    let (element, values) = match header {
+        SoapHeader::InitChallenge { user_id } => ("InitChallenge", vec![("UserID", user_id)]),
+        SoapHeader::ClientAuth { nonce, auth, user_id, realm } => (
+            "ClientAuth",
+            vec![("Nonce", nonce), ("Auth", auth), ("UserID", user_id), ("Realm", realm)],
+        ),
+    };
+    let mut header = format!(
+        "<s:Header>\n<h:{} xmlns:h=\"{}\" s:mustUnderstand=\"1\">\n",
+        element, AUTHENTICATION_NAMESPACE
+    );
+    for (name, value) in values {
+        header.push_str(&format!("<{}>{}</{}>\n", name, escape_xml(value), name));
+    }
+    header.push_str(&format!("</h:{}>\n</s:Header>\n", element));
+
+    header
+}
+
+}
+pub mod tr064_wlan_configuration {
+use super::super::soap::SoapAction;
+pub fn generate_set_enable_request(new_enable: bool) -> (String, String, String) {
+
+    let request = SetEnableRequest {
+        new_enable,
+    };
+
+    (SetEnableRequest::CONTROL_URL.to_string(), request.soap_action_header(), request.body())
+}
+
+/// Arguments of the action `SetEnable`.
+#[derive(Debug)]
+pub struct SetEnableRequest {
+    pub new_enable: bool,
+}
+
+impl SoapAction for SetEnableRequest {
+    type Response = super::super::tr064_responses::tr064_wlan_configuration::SetEnableResponse;
+
+    const CONTROL_URL: &'static str = "/upnp/control/wlanconfig1";
+    const SERVICE_TYPE: &'static str = "urn:dslforum-org:service:WLANConfiguration:1";
+    const ACTION_NAME: &'static str = "SetEnable";
+
+    fn body_with_header(&self, header: Option<&super::super::soap::SoapHeader>) -> String {
+        let SetEnableRequest {
+            new_enable,
+        } = self;
+        super::tr064_multi_use::envelope_with_header(
+            header,
+            Self::SERVICE_TYPE,
+            Self::ACTION_NAME,
+            &[
+                ("NewEnable", u8::from(*new_enable).to_string()),
+            ],
+        )
+    }
+}
+
+/// Needs the access right `App`.
+pub fn generate_get_info_request() -> (String, String, String) {
+
+    let request = GetInfoRequest;
+
+    (GetInfoRequest::CONTROL_URL.to_string(), request.soap_action_header(), request.body())
+}
+
+/// Arguments of the action `GetInfo`.
+///
+/// Needs the access right `App`.
+#[derive(Debug)]
+pub struct GetInfoRequest;
+
+impl SoapAction for GetInfoRequest {
+    type Response = super::super::tr064_responses::tr064_wlan_configuration::GetInfoResponse;
+
+    const CONTROL_URL: &'static str = "/upnp/control/wlanconfig1";
+    const SERVICE_TYPE: &'static str = "urn:dslforum-org:service:WLANConfiguration:1";
+    const ACTION_NAME: &'static str = "GetInfo";
+    const ACCESS_RIGHT: Option<super::super::soap::AccessRight> = Some(super::super::soap::AccessRight::App);
+
+    fn body_with_header(&self, header: Option<&super::super::soap::SoapHeader>) -> String {
+        super::tr064_multi_use::envelope_with_header(header, Self::SERVICE_TYPE, Self::ACTION_NAME, &[])
+    }
+}
+
+pub fn generate_set_ssid_request(new_ssid: &str) -> (String, String, String) {
+
+    let request = SetSSIDRequest {
+        new_ssid: new_ssid.to_string(),
+    };
+
+    (SetSSIDRequest::CONTROL_URL.to_string(), request.soap_action_header(), request.body())
+}
+
+/// Arguments of the action `SetSSID`.
+#[derive(Debug)]
+pub struct SetSSIDRequest {
+    pub new_ssid: String,
+}
+
+impl SoapAction for SetSSIDRequest {
+    type Response = super::super::tr064_responses::tr064_wlan_configuration::SetSSIDResponse;
+
+    const CONTROL_URL: &'static str = "/upnp/control/wlanconfig1";
+    const SERVICE_TYPE: &'static str = "urn:dslforum-org:service:WLANConfiguration:1";
+    const ACTION_NAME: &'static str = "SetSSID";
+
+    fn body_with_header(&self, header: Option<&super::super::soap::SoapHeader>) -> String {
+        let SetSSIDRequest {
+            new_ssid,
+        } = self;
+        super::tr064_multi_use::envelope_with_header(
+            header,
+            Self::SERVICE_TYPE,
+            Self::ACTION_NAME,
+            &[
+                ("NewSSID", new_ssid.to_string()),
+            ],
+        )
+    }
+}
+
+pub fn generate_set_channel_request(new_channel: u32) -> (String, String, String) {
+
+    let request = SetChannelRequest {
+        new_channel,
+    };
+
+    (SetChannelRequest::CONTROL_URL.to_string(), request.soap_action_header(), request.body())
+}
+
+/// Arguments of the action `SetChannel`.
+#[derive(Debug)]
+pub struct SetChannelRequest {
+    pub new_channel: u32,
+}
+
+impl SoapAction for SetChannelRequest {
+    type Response = super::super::tr064_responses::tr064_wlan_configuration::SetChannelResponse;
+
+    const CONTROL_URL: &'static str = "/upnp/control/wlanconfig1";
+    const SERVICE_TYPE: &'static str = "urn:dslforum-org:service:WLANConfiguration:1";
+    const ACTION_NAME: &'static str = "SetChannel";
+
+    fn body_with_header(&self, header: Option<&super::super::soap::SoapHeader>) -> String {
+        let SetChannelRequest {
+            new_channel,
+        } = self;
+        super::tr064_multi_use::envelope_with_header(
+            header,
+            Self::SERVICE_TYPE,
+            Self::ACTION_NAME,
+            &[
+                ("NewChannel", new_channel.to_string()),
+            ],
+        )
+    }
+}
+
+/// Calls the actions of the service through a `FritzClient`.
+pub struct Client<'c> {
+    client: &'c super::super::client::FritzClient,
+}
+
+impl super::super::client::FritzClient {
+    /// Calls the actions of the service of `tr064_wlan_configuration`.
+    pub fn tr064_wlan_configuration(&self) -> Client<'_> {
+        Client { client: self }
+    }
+}
+
+impl Client<'_> {
+    /// Calls `SetEnable`.
+    pub fn set_enable(&self, new_enable: bool) -> Result<super::super::tr064_responses::tr064_wlan_configuration::SetEnableResponse, super::super::client::ClientError> {
+        let request = SetEnableRequest {
+            new_enable,
+        };
+        self.client.call(&request)
+    }
+
+    /// Calls `GetInfo`.
+    ///
+    /// Needs the access right `App`.
+    pub fn get_info(&self) -> Result<super::super::tr064_responses::tr064_wlan_configuration::GetInfoResponse, super::super::client::ClientError> {
+        let request = GetInfoRequest;
+        self.client.call(&request)
+    }
+
+    /// Calls `SetSSID`.
+    pub fn set_ssid(&self, new_ssid: &str) -> Result<super::super::tr064_responses::tr064_wlan_configuration::SetSSIDResponse, super::super::client::ClientError> {
+        let request = SetSSIDRequest {
+            new_ssid: new_ssid.to_string(),
+        };
+        self.client.call(&request)
+    }
+
+    /// Calls `SetChannel`.
+    pub fn set_channel(&self, new_channel: u32) -> Result<super::super::tr064_responses::tr064_wlan_configuration::SetChannelResponse, super::super::client::ClientError> {
+        let request = SetChannelRequest {
+            new_channel,
+        };
+        self.client.call(&request)
+    }
+}
+/// Calls the actions of the service through an `AsyncFritzClient`.
+pub struct AsyncClient<'c> {
+    client: &'c super::super::client::AsyncFritzClient,
+}
+
+impl super::super::client::AsyncFritzClient {
+    /// Calls the actions of the service of `tr064_wlan_configuration`.
+    pub fn tr064_wlan_configuration(&self) -> AsyncClient<'_> {
+        AsyncClient { client: self }
+    }
+}
+
+impl AsyncClient<'_> {
+    /// Calls `SetEnable`.
+    pub async fn set_enable(&self, new_enable: bool) -> Result<super::super::tr064_responses::tr064_wlan_configuration::SetEnableResponse, super::super::client::ClientError> {
+        let request = SetEnableRequest {
+            new_enable,
+        };
+        self.client.call(&request).await
+    }
+
+    /// Calls `GetInfo`.
+    ///
+    /// Needs the access right `App`.
+    pub async fn get_info(&self) -> Result<super::super::tr064_responses::tr064_wlan_configuration::GetInfoResponse, super::super::client::ClientError> {
+        let request = GetInfoRequest;
+        self.client.call(&request).await
+    }
+
+    /// Calls `SetSSID`.
+    pub async fn set_ssid(&self, new_ssid: &str) -> Result<super::super::tr064_responses::tr064_wlan_configuration::SetSSIDResponse, super::super::client::ClientError> {
+        let request = SetSSIDRequest {
+            new_ssid: new_ssid.to_string(),
+        };
+        self.client.call(&request).await
+    }
+
+    /// Calls `SetChannel`.
+    pub async fn set_channel(&self, new_channel: u32) -> Result<super::super::tr064_responses::tr064_wlan_configuration::SetChannelResponse, super::super::client::ClientError> {
+        let request = SetChannelRequest {
+            new_channel,
+        };
+        self.client.call(&request).await
+    }
+}
+
+}
+}
+pub mod tr064_responses {
+pub mod tr064_device_config {
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct GetPersistentDataResponse {
+    #[serde(rename = "NewPersistentData")]
+    pub new_persistent_data: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SetPersistentDataResponse {
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RebootResponse {
+}
+
+#[derive(Deserialize, Debug)]
+pub struct XAVMDECreateUrlSIDResponse {
+    #[serde(rename = "NewX_AVM-DE_UrlSID")]
+    pub new_x_avm_de_url_sid: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct XAVMDESetConfigFileResponse {
+}
+
+
+
+}
+pub mod tr064_multi_use {
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct Envelope<T> {
+    #[serde(rename = "Body")]
+    pub body: Body<T>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Body<T> {
+    #[serde(
+        alias = "GetPersistentDataResponse",
+        alias = "SetPersistentDataResponse",
+        alias = "RebootResponse",
+        alias = "X_AVM-DE_CreateUrlSIDResponse",
+        alias = "X_AVM-DE_SetConfigFileResponse",
+        alias = "SetEnableResponse",
+        alias = "GetInfoResponse",
+        alias = "SetSSIDResponse",
+        alias = "SetChannelResponse",
+    )]
+    pub response: T,
+}
+
+/// An envelope holding either the response `T` or the `Fault` the FRITZ!Box answered a failed action with.
+#[derive(Deserialize, Debug)]
+pub struct ResultEnvelope<T> {
+    #[serde(rename = "Body")]
+    pub body: ResultBody<T>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResultBody<T> {
+    #[serde(rename = "Fault")]
+    pub fault: Option<super::super::soap::Fault>,
+    #[serde(
+        alias = "GetPersistentDataResponse",
+        alias = "SetPersistentDataResponse",
+        alias = "RebootResponse",
+        alias = "X_AVM-DE_CreateUrlSIDResponse",
+        alias = "X_AVM-DE_SetConfigFileResponse",
+        alias = "SetEnableResponse",
+        alias = "GetInfoResponse",
+        alias = "SetSSIDResponse",
+        alias = "SetChannelResponse",
+    )]
+    pub response: Option<T>,
+}
+
+impl<T> ResultEnvelope<T> {
+    /// Returns the response or the fault, `None` if the body holds neither.
+    pub fn into_result(self) -> Option<Result<T, super::super::soap::Fault>> {
+        match self.body.fault {
+            Some(fault) => Some(Err(fault)),
+            None => self.body.response.map(Ok),
+        }
+    }
+}
+}
+pub mod tr064_wlan_configuration {
+use serde::Deserialize;
+
+/// Default value of the state variable `Enable` as given in the description, the string sent for it
+/// whatever the type of the state variable, e.g. `"1"` for a `boolean`.
+pub const DEFAULT_ENABLE: &str = "1";
+
+#[derive(Deserialize, Debug)]
+pub struct SetEnableResponse {
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GetInfoResponse {
+    #[serde(rename = "NewEnable")]
+    pub new_enable: bool,
+    #[serde(rename = "NewStatus")]
+    pub new_status: String,
+    #[serde(rename = "NewChannel")]
+    pub new_channel: u32,
+    #[serde(rename = "NewSSID")]
+    pub new_ssid: String,
+    #[serde(rename = "NewStandard")]
+    pub new_standard: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SetSSIDResponse {
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SetChannelResponse {
+}
+
+
+
+}
+}
@@ -0,0 +1,90 @@
+//! Keeps `tests/fixtures/generated` in sync with the generator, the other tests compile and exercise these files.
+//! Both clients with certificate pinning and a few access rights are generated too, so their tests can use the same files.
+//! The services of `tests/fixtures/options` are generated one by one with the options they exercise, `X_Primitives` with
+//! the defaults.
+
+use std::env;
+use std::fs;
+use fritz_box_tr064_igd_api_files_generator::{Generator, MemorySink};
+use fritz_box_tr064_igd_api_files_generator::api_handling::source::DescriptionSource;
+use fritz_box_tr064_igd_api_files_generator::config::{
+    AccessRight, AllowedValuesMapping, BinaryMapping, ClientFlavour, DateTimeMapping, Description, RangeMapping, UuidMapping,
+};
+
+const SNAPSHOT: &str = "tests/fixtures/snapshot";
+const GENERATED: &str = "tests/fixtures/generated/fritz_box_api.rs";
+const OPTIONS_SNAPSHOT: &str = "tests/fixtures/options";
+
+/// Generates the root module with `generator` and compares it to `path`, or writes it if `UPDATE_GENERATED` is set.
+fn check(generator: Generator<'_>, path: &str) {
+    let mut sink = MemorySink::default();
+    generator.sink(&mut sink).generate().unwrap();
+    let generated = sink.root_module();
+
+    if env::var_os("UPDATE_GENERATED").is_some() {
+        fs::write(path, &generated).unwrap();
+    }
+    assert!(
+        generated == fs::read_to_string(path).unwrap(),
+        "{} is outdated, run `UPDATE_GENERATED=1 cargo test --test generated` to update it",
+        path
+    );
+}
+
+/// A generator for the single service `service` of `tests/fixtures/options`.
+fn options_generator(service: &str) -> Generator<'static> {
+    Generator::new()
+        .source(DescriptionSource::Snapshot(OPTIONS_SNAPSHOT.into()))
+        .descriptions(&[Description::Tr064])
+        .include_service(service)
+}
+
+#[test]
+fn generated_files_are_up_to_date() {
+    let generator = Generator::new()
+        .source(DescriptionSource::Snapshot(SNAPSHOT.into()))
+        .client_flavour(ClientFlavour::Both)
+        .client_tls(true)
+        .access_right("DeviceConfig", "Reboot", AccessRight::Settings)
+        .access_right("urn:dslforum-org:service:WLANConfiguration:1", "GetInfo", AccessRight::App);
+
+    check(generator, GENERATED);
+}
+
+#[test]
+fn typed_values_are_up_to_date() {
+    let generator = options_generator("X_Types")
+        .date_time(DateTimeMapping::Chrono)
+        .uuid(UuidMapping::Uuid)
+        .binary(BinaryMapping::Bytes);
+
+    check(generator, "tests/fixtures/generated/typed_values_api.rs");
+}
+
+#[test]
+fn allowed_values_are_up_to_date() {
+    let generator = options_generator("X_AllowedValues").allowed_values(AllowedValuesMapping::Enum, false);
+    check(generator, "tests/fixtures/generated/allowed_values_api.rs");
+
+    let generator = options_generator("X_AllowedValues").allowed_values(AllowedValuesMapping::Enum, true);
+    check(generator, "tests/fixtures/generated/allowed_values_unknown_api.rs");
+}
+
+#[test]
+fn ranges_are_up_to_date() {
+    let generator = options_generator("X_Ranges").ranges(RangeMapping::Checked);
+
+    check(generator, "tests/fixtures/generated/ranges_api.rs");
+}
+
+#[test]
+fn optional_defaults_are_up_to_date() {
+    let generator = options_generator("X_Defaults").optional_defaults(true);
+
+    check(generator, "tests/fixtures/generated/optional_defaults_api.rs");
+}
+
+#[test]
+fn primitives_are_up_to_date() {
+    check(options_generator("X_Primitives"), "tests/fixtures/generated/primitives_api.rs");
+}
@@ -0,0 +1,67 @@
+//! Summarizes and compares descriptions, `tests/fixtures/nested` adds a second `WLANConfiguration` on another device to
+//! `tests/fixtures/snapshot`.
+
+use fritz_box_tr064_igd_api_files_generator::api_handling::inspect::{diff, summarize, ServiceKey};
+use fritz_box_tr064_igd_api_files_generator::api_handling::source::DescriptionSource;
+
+const SNAPSHOT: &str = "tests/fixtures/snapshot";
+const NESTED: &str = "tests/fixtures/nested";
+const WLAN: &str = "urn:dslforum-org:service:WLANConfiguration:1";
+
+fn key(service_type: &str, control_url: &str) -> ServiceKey {
+    ServiceKey {
+        service_type: service_type.to_string(),
+        control_url: control_url.to_string(),
+    }
+}
+
+#[test]
+fn services_of_the_same_type_are_kept() {
+    let summary = summarize(&DescriptionSource::Snapshot(NESTED.into()), "/tr64desc.xml").unwrap();
+
+    let keys: Vec<&ServiceKey> = summary.services.keys().collect();
+    assert_eq!(
+        keys,
+        [
+            &key("urn:dslforum-org:service:DeviceConfig:1", "/upnp/control/deviceconfig"),
+            &key(WLAN, "/upnp/control/wlanconfig1"),
+            &key(WLAN, "/upnp/control/wlanconfig2"),
+        ]
+    );
+    let first = &summary.services[&key(WLAN, "/upnp/control/wlanconfig1")];
+    let second = &summary.services[&key(WLAN, "/upnp/control/wlanconfig2")];
+    assert_eq!(first, second);
+    assert_eq!(
+        first.actions["GetInfo"].to_string(),
+        "() -> (NewEnable: boolean, NewStatus: string, NewChannel: ui1, NewSSID: string, NewStandard: string)"
+    );
+}
+
+#[test]
+fn added_service_of_the_same_type_is_listed() {
+    let old = summarize(&DescriptionSource::Snapshot(SNAPSHOT.into()), "/tr64desc.xml").unwrap();
+    let new = summarize(&DescriptionSource::Snapshot(NESTED.into()), "/tr64desc.xml").unwrap();
+
+    assert_eq!(diff(&old, &new), ["+ urn:dslforum-org:service:WLANConfiguration:1 (/upnp/control/wlanconfig2)"]);
+    assert_eq!(diff(&new, &old), ["- urn:dslforum-org:service:WLANConfiguration:1 (/upnp/control/wlanconfig2)"]);
+    assert!(diff(&new, &new).is_empty());
+}
+
+#[test]
+fn changed_action_is_listed() {
+    let old = summarize(&DescriptionSource::Snapshot(NESTED.into()), "/tr64desc.xml").unwrap();
+    let mut new = summarize(&DescriptionSource::Snapshot(NESTED.into()), "/tr64desc.xml").unwrap();
+    let second = new.services.get_mut(&key(WLAN, "/upnp/control/wlanconfig2")).unwrap();
+    second.actions.remove("SetEnable");
+    second.actions.get_mut("SetChannel").unwrap().in_arguments[0].1 = "ui2".to_string();
+
+    assert_eq!(
+        diff(&old, &new),
+        [
+            "~ urn:dslforum-org:service:WLANConfiguration:1 (/upnp/control/wlanconfig2)",
+            "    ~ SetChannel(NewChannel: ui1) -> ()",
+            "      SetChannel(NewChannel: ui2) -> ()",
+            "    - SetEnable(NewEnable: boolean) -> ()",
+        ]
+    );
+}
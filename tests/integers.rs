@@ -0,0 +1,75 @@
+//! Checks the rust types of the UPnP integer types `ui1`, `ui2`, `i1` and `i2` of
+//! `tests/fixtures/options/x_primitivesSCPD.xml` with both integer mappings.
+
+use fritz_box_tr064_igd_api_files_generator::{Generator, MemorySink};
+use fritz_box_tr064_igd_api_files_generator::api_handling::source::DescriptionSource;
+use fritz_box_tr064_igd_api_files_generator::config::{Description, IntegerMapping};
+
+const OPTIONS_SNAPSHOT: &str = "tests/fixtures/options";
+
+/// Generated request and response file of `X_Primitives`.
+struct PrimitivesFiles {
+    requests: String,
+    responses: String,
+}
+
+fn generate(integers: IntegerMapping) -> PrimitivesFiles {
+    let mut sink = MemorySink::default();
+    Generator::new()
+        .source(DescriptionSource::Snapshot(OPTIONS_SNAPSHOT.into()))
+        .descriptions(&[Description::Tr064])
+        .include_service("X_Primitives")
+        .integers(integers)
+        .sink(&mut sink)
+        .generate()
+        .unwrap();
+    let file = |folder: &str| {
+        sink.files
+            .iter()
+            .find(|(path, _)| path.ends_with(format!("{}/tr064_x_primitives.rs", folder)))
+            .map(|(_, content)| content.clone())
+            .unwrap_or_else(|| panic!("no {} file in {:?}", folder, sink.files.keys()))
+    };
+
+    PrimitivesFiles {
+        requests: file("tr064_requests"),
+        responses: file("tr064_responses"),
+    }
+}
+
+/// Asserts that the request struct, the request function and the response struct declare `field` as `type_name`.
+fn assert_type(files: &PrimitivesFiles, field: &str, type_name: &str) {
+    let declaration = format!("pub {}: {},", field, type_name);
+    let function = files
+        .requests
+        .lines()
+        .find(|line| line.starts_with("pub fn generate_set_values_request("))
+        .unwrap();
+
+    assert!(files.responses.contains(&declaration), "{}", files.responses);
+    assert!(files.requests.contains(&declaration), "{}", files.requests);
+    assert!(function.contains(&format!("{}: {},", field, type_name)), "{}", function);
+}
+
+#[test]
+fn exact_integers_match_the_upnp_ranges() {
+    let files = generate(IntegerMapping::Exact);
+
+    assert_type(&files, "new_byte", "u8");
+    assert_type(&files, "new_word", "u16");
+    assert_type(&files, "new_tiny", "i8");
+    assert_type(&files, "new_short", "i16");
+    // the 32 and 64 bit types are the same with both mappings
+    assert_type(&files, "new_long", "u64");
+    assert_type(&files, "new_int", "i32");
+}
+
+#[test]
+fn integers_are_widened_by_default() {
+    let files = generate(IntegerMapping::default());
+
+    assert_type(&files, "new_byte", "u32");
+    assert_type(&files, "new_word", "u32");
+    assert_type(&files, "new_tiny", "i32");
+    assert_type(&files, "new_short", "i32");
+}
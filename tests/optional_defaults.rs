@@ -0,0 +1,68 @@
+//! Sends the default values of the state variables for arguments left out with `None` and names their constants.
+
+use fritz_box_tr064_igd_api_files_generator::{Generator, MemorySink};
+use fritz_box_tr064_igd_api_files_generator::api_handling::source::DescriptionSource;
+use fritz_box_tr064_igd_api_files_generator::config::Description;
+
+#[allow(dead_code)]
+mod api {
+    include!("fixtures/generated/optional_defaults_api.rs");
+}
+
+use api::soap::SoapAction;
+use api::tr064_requests::tr064_x_defaults::{generate_set_config_request, SetConfigRequest};
+use api::tr064_responses::tr064_x_defaults::{DEFAULT_ENABLE, DEFAULT_INTERVAL, DEFAULT_NAME};
+
+#[test]
+fn defaults_hold_the_wire_strings() {
+    assert_eq!(DEFAULT_ENABLE, "1");
+    assert_eq!(DEFAULT_INTERVAL, "60");
+    assert_eq!(DEFAULT_NAME, "FRITZ!Box & Co");
+}
+
+#[test]
+fn none_sends_the_default() {
+    let (control_url, soap_action, body) = generate_set_config_request(None, None, None, "comment");
+
+    assert_eq!(control_url, "/upnp/control/x_defaults");
+    assert_eq!(soap_action, "urn:dslforum-org:service:X_Defaults:1#SetConfig");
+    assert!(body.contains("<NewEnable>1</NewEnable>"), "{}", body);
+    assert!(body.contains("<NewInterval>60</NewInterval>"), "{}", body);
+    assert!(body.contains("<NewName>FRITZ!Box &amp; Co</NewName>"), "{}", body);
+    assert!(body.contains("<NewComment>comment</NewComment>"), "{}", body);
+}
+
+#[test]
+fn some_sends_the_value() {
+    let body = SetConfigRequest {
+        new_enable: Some(false),
+        new_interval: Some(300),
+        new_name: Some("box".to_string()),
+        new_comment: String::new(),
+    }
+    .body();
+
+    assert!(body.contains("<NewEnable>0</NewEnable>"), "{}", body);
+    assert!(body.contains("<NewInterval>300</NewInterval>"), "{}", body);
+    assert!(body.contains("<NewName>box</NewName>"), "{}", body);
+    assert!(body.contains("<NewComment></NewComment>"), "{}", body);
+}
+
+#[test]
+fn constants_follow_the_naming() {
+    let mut sink = MemorySink::default();
+    Generator::new()
+        .source(DescriptionSource::Snapshot("tests/fixtures/options".into()))
+        .descriptions(&[Description::Tr064])
+        .include_service("X_Defaults")
+        .optional_defaults(true)
+        .naming("Interval", "poll_interval")
+        .sink(&mut sink)
+        .generate()
+        .unwrap();
+    let generated = sink.root_module();
+
+    assert!(generated.contains("pub const DEFAULT_POLL_INTERVAL: &str = \"60\";"), "{}", generated);
+    assert!(generated.contains("tr064_x_defaults::DEFAULT_POLL_INTERVAL.to_string()"), "{}", generated);
+    assert!(!generated.contains("DEFAULT_INTERVAL"), "{}", generated);
+}
@@ -0,0 +1,106 @@
+//! Deserializes and formats the integer, float, `char` and textual types of `tests/fixtures/options/x_primitivesSCPD.xml`
+//! with the default mapping, which widens the integers.
+
+#[allow(dead_code, clippy::too_many_arguments)]
+mod api {
+    include!("fixtures/generated/primitives_api.rs");
+}
+
+use api::soap::{response, SoapAction};
+use api::tr064_requests::tr064_x_primitives::{GetValuesRequest, SetValuesRequest};
+
+fn values_response(values: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\"?>\n\
+         <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\n\
+         <s:Body>\n<u:GetValuesResponse xmlns:u=\"urn:dslforum-org:service:X_Primitives:1\">{}</u:GetValuesResponse>\n\
+         </s:Body>\n</s:Envelope>",
+        values
+    )
+}
+
+const VALUES: &str = "<NewByte>255</NewByte><NewWord>65535</NewWord><NewLong>18446744073709551615</NewLong>\
+    <NewTiny>-128</NewTiny><NewShort>-32768</NewShort><NewSigned>-9223372036854775808</NewSigned><NewInt>-1</NewInt>\
+    <NewSingle>1.5</NewSingle><NewDouble>-2.25</NewDouble><NewNumber>1000</NewNumber><NewFixed>12.3456</NewFixed>\
+    <NewFloat>0.5</NewFloat><NewChar>ä</NewChar><NewTime>13:37:00</NewTime><NewTimeTz>13:37:00+01:00</NewTimeTz>\
+    <NewUri>http://fritz.box/</NewUri>";
+
+#[test]
+fn values_are_deserialized() {
+    let values = response::<GetValuesRequest>(&values_response(VALUES)).unwrap();
+
+    assert_eq!(values.new_byte, 255);
+    assert_eq!(values.new_word, 65535);
+    assert_eq!(values.new_long, u64::MAX);
+    assert_eq!(values.new_tiny, -128);
+    assert_eq!(values.new_short, -32768);
+    assert_eq!(values.new_signed, i64::MIN);
+    assert_eq!(values.new_int, -1);
+    assert_eq!(values.new_single, 1.5);
+    assert_eq!(values.new_double, -2.25);
+    assert_eq!(values.new_number, 1000.0);
+    assert_eq!(values.new_fixed, 12.3456);
+    assert_eq!(values.new_float, 0.5);
+    assert_eq!(values.new_char, 'ä');
+    // times and URIs are kept as sent
+    assert_eq!(values.new_time, "13:37:00");
+    assert_eq!(values.new_time_tz, "13:37:00+01:00");
+    assert_eq!(values.new_uri, "http://fritz.box/");
+}
+
+#[test]
+fn invalid_values_fail() {
+    let too_long = VALUES.replace("18446744073709551615", "18446744073709551616");
+    let two_chars = VALUES.replace("<NewChar>ä</NewChar>", "<NewChar>äh</NewChar>");
+    let not_a_number = VALUES.replace("12.3456", "twelve");
+
+    assert!(response::<GetValuesRequest>(&values_response(&too_long)).is_err());
+    assert!(response::<GetValuesRequest>(&values_response(&two_chars)).is_err());
+    assert!(response::<GetValuesRequest>(&values_response(&not_a_number)).is_err());
+}
+
+#[test]
+fn values_are_formatted() {
+    let request = SetValuesRequest {
+        new_byte: 255,
+        new_word: 65535,
+        new_long: u64::MAX,
+        new_tiny: -128,
+        new_short: -32768,
+        new_signed: i64::MIN,
+        new_int: -1,
+        new_single: 1.5,
+        new_double: -2.25,
+        new_number: 1000.0,
+        new_fixed: 12.3456,
+        new_float: 0.5,
+        new_char: '<',
+        new_time: "13:37:00".to_string(),
+        new_time_tz: "13:37:00+01:00".to_string(),
+        new_uri: "http://fritz.box/?a=1&b=2".to_string(),
+    };
+
+    let body = request.body();
+
+    for (name, value) in [
+        ("NewByte", "255"),
+        ("NewWord", "65535"),
+        ("NewLong", "18446744073709551615"),
+        ("NewTiny", "-128"),
+        ("NewShort", "-32768"),
+        ("NewSigned", "-9223372036854775808"),
+        ("NewInt", "-1"),
+        ("NewSingle", "1.5"),
+        ("NewDouble", "-2.25"),
+        // whole floats are formatted without a fraction
+        ("NewNumber", "1000"),
+        ("NewFixed", "12.3456"),
+        ("NewFloat", "0.5"),
+        ("NewChar", "&lt;"),
+        ("NewTime", "13:37:00"),
+        ("NewTimeTz", "13:37:00+01:00"),
+        ("NewUri", "http://fritz.box/?a=1&amp;b=2"),
+    ] {
+        assert!(body.contains(&format!("<{0}>{1}</{0}>", name, value)), "{}", body);
+    }
+}
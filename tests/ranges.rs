@@ -0,0 +1,84 @@
+//! Checks the newtypes generated from allowed value ranges, their bounds, steps and errors.
+
+#[allow(dead_code)]
+mod api {
+    include!("fixtures/generated/ranges_api.rs");
+}
+
+use api::soap::{response, SoapAction};
+use api::tr064_requests::tr064_x_ranges::{GetLevelsRequest, SetLevelsRequest};
+use api::tr064_responses::tr064_multi_use::RangeError;
+use api::tr064_responses::tr064_x_ranges::{Frequency, Offset, Percentage};
+
+#[test]
+fn boundaries_are_accepted() {
+    assert_eq!(Percentage::new(0).unwrap().get(), Percentage::MIN);
+    assert_eq!(Percentage::new(100).unwrap().get(), Percentage::MAX);
+    assert_eq!(Frequency::new(2412).unwrap().get(), 2412);
+    assert_eq!(Frequency::new(2472).unwrap().get(), 2472);
+    assert_eq!(Offset::new(-9).unwrap().get(), -9);
+    assert_eq!(Offset::new(9).unwrap().get(), 9);
+}
+
+#[test]
+fn values_outside_are_refused() {
+    let error = Percentage::new(101).unwrap_err();
+
+    assert_eq!(
+        error,
+        RangeError {
+            variable: "Percentage",
+            value: 101,
+            minimum: 0,
+            maximum: 100,
+            step: 1,
+        }
+    );
+    assert_eq!(error.to_string(), "101 isn't an allowed value of Percentage, expected 0 to 100 in steps of 1");
+    assert!(Frequency::new(2411).is_err());
+    assert!(Frequency::new(2477).is_err());
+    assert!(Offset::new(-12).is_err());
+    assert!(Offset::new(12).is_err());
+}
+
+#[test]
+fn values_off_step_are_refused() {
+    // steps count from the minimum, 2437 is channel 6
+    assert!(Frequency::new(2437).is_ok());
+    assert_eq!(Frequency::new(2440).unwrap_err().step, 5);
+    assert!(Offset::new(0).is_ok());
+    assert!(Offset::new(-6).is_ok());
+    assert!(Offset::new(1).is_err());
+    assert!(Offset::new(-7).is_err());
+    // without a step every value in the range is on one
+    assert!((Percentage::MIN..=Percentage::MAX).all(|value| Percentage::new(value).is_ok()));
+}
+
+#[test]
+fn responses_are_not_checked() {
+    let response_body = "<?xml version=\"1.0\"?>\n\
+        <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\n\
+        <s:Body>\n<u:GetLevelsResponse xmlns:u=\"urn:dslforum-org:service:X_Ranges:1\">\
+        <NewPercentage>150</NewPercentage><NewFrequency>2437</NewFrequency><NewOffset>-1</NewOffset>\
+        </u:GetLevelsResponse>\n</s:Body>\n</s:Envelope>";
+
+    let levels = response::<GetLevelsRequest>(response_body).unwrap();
+
+    assert_eq!(levels.new_percentage.get(), 150);
+    assert_eq!(levels.new_frequency, Frequency::new(2437).unwrap());
+    assert_eq!(levels.new_offset.get(), -1);
+}
+
+#[test]
+fn values_are_formatted() {
+    let body = SetLevelsRequest {
+        new_percentage: Percentage::new(42).unwrap(),
+        new_frequency: Frequency::new(2472).unwrap(),
+        new_offset: Offset::new(-9).unwrap(),
+    }
+    .body();
+
+    assert!(body.contains("<NewPercentage>42</NewPercentage>"), "{}", body);
+    assert!(body.contains("<NewFrequency>2472</NewFrequency>"), "{}", body);
+    assert!(body.contains("<NewOffset>-9</NewOffset>"), "{}", body);
+}
@@ -0,0 +1,162 @@
+//! Takes a snapshot of `tests/fixtures/snapshot` and checks the mirrored files and the manifest. Generating from the
+//! snapshot folder has to give the same files as generating from a FRITZ!Box serving it.
+
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::thread;
+use fritz_box_tr064_igd_api_files_generator::{Generator, MemorySink};
+use fritz_box_tr064_igd_api_files_generator::api_handling::helper::format_unix_timestamp;
+use fritz_box_tr064_igd_api_files_generator::api_handling::inspect::summarize;
+use fritz_box_tr064_igd_api_files_generator::api_handling::snapshot::{take_snapshot, MANIFEST_FILE_NAME};
+use fritz_box_tr064_igd_api_files_generator::api_handling::source::DescriptionSource;
+
+const SNAPSHOT: &str = "tests/fixtures/snapshot";
+
+/// A folder in the temp dir, removed when dropped.
+struct TempFolder(PathBuf);
+
+impl TempFolder {
+    fn new(name: &str) -> Self {
+        TempFolder(env::temp_dir().join(format!("fritzgen-{}-{}", name, std::process::id())))
+    }
+}
+
+impl Drop for TempFolder {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Serves the files of `SNAPSHOT` over HTTP like a FRITZ!Box, returns the base URL.
+fn serve_snapshot() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut reader = BufReader::new(stream.unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            // the headers end with an empty line, the `GET` requests have no body
+            let mut line = String::new();
+            loop {
+                line.clear();
+                reader.read_line(&mut line).unwrap();
+                if line.trim_end().is_empty() {
+                    break;
+                }
+            }
+
+            let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+            let (status, body) = match fs::read_to_string(format!("{}{}", SNAPSHOT, path)) {
+                Ok(description) => (200, description),
+                Err(_) => (404, String::new()),
+            };
+            write!(
+                reader.into_inner(),
+                "HTTP/1.1 {} Stand-in\r\nContent-Type: text/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+        }
+    });
+
+    base_url
+}
+
+fn generate(source: DescriptionSource) -> MemorySink {
+    let mut sink = MemorySink::default();
+    Generator::new().source(source).sink(&mut sink).generate().unwrap();
+    sink
+}
+
+#[test]
+fn timestamps_are_formatted() {
+    assert_eq!(format_unix_timestamp(0), "1970-01-01T00:00:00Z");
+    assert_eq!(format_unix_timestamp(946_684_799), "1999-12-31T23:59:59Z");
+    assert_eq!(format_unix_timestamp(1_712_065_020), "2024-04-02T13:37:00Z");
+}
+
+#[test]
+fn leap_years_are_formatted() {
+    // 2000 is a leap year as it is divisible by 400, 2100 isn't as it is divisible by 100
+    assert_eq!(format_unix_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+    assert_eq!(format_unix_timestamp(1_709_251_199), "2024-02-29T23:59:59Z");
+    assert_eq!(format_unix_timestamp(1_709_251_200), "2024-03-01T00:00:00Z");
+    assert_eq!(format_unix_timestamp(4_107_542_400 - 1), "2100-02-28T23:59:59Z");
+    assert_eq!(format_unix_timestamp(4_107_542_400), "2100-03-01T00:00:00Z");
+}
+
+#[test]
+fn snapshot_mirrors_the_descriptions() {
+    let folder = TempFolder::new("snapshot");
+    let source = DescriptionSource::Snapshot(SNAPSHOT.into());
+
+    let manifest = take_snapshot(&source, &["/tr64desc.xml", "/igddesc.xml"], &folder.0).unwrap();
+
+    let files = [
+        "tr64desc.xml",
+        "deviceconfigSCPD.xml",
+        "wlanconfigSCPD.xml",
+        "igddesc.xml",
+        "igdupnp/any.xml",
+    ];
+    assert_eq!(manifest.files, files);
+    for file in &files {
+        let original = fs::read_to_string(PathBuf::from(SNAPSHOT).join(file)).unwrap();
+        assert_eq!(fs::read_to_string(folder.0.join(file)).unwrap(), original, "{}", file);
+    }
+    let mirrored = DescriptionSource::Snapshot(folder.0.clone());
+    assert_eq!(
+        summarize(&mirrored, "/tr64desc.xml").unwrap().services,
+        summarize(&source, "/tr64desc.xml").unwrap().services
+    );
+}
+
+#[test]
+fn manifest_is_written() {
+    let folder = TempFolder::new("manifest");
+
+    let manifest = take_snapshot(&DescriptionSource::Snapshot(SNAPSHOT.into()), &["/tr64desc.xml"], &folder.0).unwrap();
+
+    let content = fs::read_to_string(folder.0.join(MANIFEST_FILE_NAME)).unwrap();
+    let written: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(written["modelName"], "FRITZ!Box 7590");
+    assert_eq!(
+        written["systemVersion"],
+        serde_json::json!({
+            "HW": 226,
+            "Major": 154,
+            "Minor": 7,
+            "Patch": 57,
+            "Buildnumber": 108230,
+            "Display": "154.07.57",
+        })
+    );
+    assert_eq!(written["files"], serde_json::json!(["tr64desc.xml", "deviceconfigSCPD.xml", "wlanconfigSCPD.xml"]));
+    assert_eq!(written["fetchTime"], manifest.fetch_time.as_str());
+    // e.g. 2024-04-02T13:37:00Z
+    let fetch_time = manifest.fetch_time.as_bytes();
+    assert_eq!(fetch_time.len(), 20, "{}", manifest.fetch_time);
+    assert_eq!((fetch_time[4], fetch_time[10], fetch_time[19]), (b'-', b'T', b'Z'), "{}", manifest.fetch_time);
+    assert!(manifest.fetch_time.as_str() > "2024", "{}", manifest.fetch_time);
+}
+
+#[test]
+fn snapshot_generates_the_files_of_the_fritz_box() {
+    let from_http = generate(DescriptionSource::Http(serve_snapshot()));
+    let from_snapshot = generate(DescriptionSource::Snapshot(SNAPSHOT.into()));
+
+    let generated = |file: &str| from_snapshot.files.keys().any(|path| path.ends_with(file));
+    assert!(generated("tr064_responses/tr064_wlan_configuration.rs"), "{:?}", from_snapshot.files.keys());
+    assert!(generated("igd_requests/igd_any.rs"), "{:?}", from_snapshot.files.keys());
+    assert_eq!(from_http.files.keys().collect::<Vec<_>>(), from_snapshot.files.keys().collect::<Vec<_>>());
+    for (path, content) in &from_snapshot.files {
+        assert!(from_http.files[path] == *content, "{} differs", path.display());
+    }
+}
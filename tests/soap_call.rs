@@ -0,0 +1,137 @@
+//! Calls the generated actions through a transport answering with canned responses.
+
+#[allow(dead_code)]
+mod api {
+    include!("fixtures/generated/fritz_box_api.rs");
+}
+
+use api::soap::{call, CallError, ErrorCode, Transport};
+use api::tr064_responses::tr064_multi_use::ResultEnvelope;
+use api::tr064_responses::tr064_wlan_configuration::GetInfoResponse;
+use api::tr064_requests::tr064_device_config::RebootRequest;
+use api::tr064_requests::tr064_wlan_configuration::{GetInfoRequest, SetSSIDRequest};
+
+/// Records the requests and answers each with `response`.
+struct CannedTransport {
+    response: String,
+    requests: Vec<(String, String, String)>,
+}
+
+impl CannedTransport {
+    fn new(response: &str) -> Self {
+        CannedTransport {
+            response: response.to_string(),
+            requests: vec![],
+        }
+    }
+}
+
+impl Transport for CannedTransport {
+    type Error = std::convert::Infallible;
+
+    fn post(&mut self, control_url: &str, soap_action: &str, body: &str) -> Result<String, Self::Error> {
+        self.requests.push((control_url.to_string(), soap_action.to_string(), body.to_string()));
+        Ok(self.response.clone())
+    }
+}
+
+fn envelope(service_type: &str, response: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\"?>\n\
+         <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\n\
+         <s:Body>\n<u:{response} xmlns:u=\"{service_type}\">{{}}</u:{response}>\n</s:Body>\n</s:Envelope>",
+        response = response,
+        service_type = service_type,
+    )
+}
+
+fn fault(error_code: u16, error_description: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\"?>\n\
+         <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\n\
+         <s:Body>\n<s:Fault>\n<faultcode>s:Client</faultcode>\n<faultstring>UPnPError</faultstring>\n<detail>\n\
+         <UPnPError xmlns=\"urn:schemas-upnp-org:control-1-0\">\n<errorCode>{}</errorCode>\n\
+         <errorDescription>{}</errorDescription>\n</UPnPError>\n</detail>\n</s:Fault>\n</s:Body>\n</s:Envelope>",
+        error_code, error_description,
+    )
+}
+
+#[test]
+fn response_is_typed() {
+    let fields = "<NewEnable>1</NewEnable><NewStatus>Up</NewStatus><NewChannel>6</NewChannel>\
+                  <NewSSID>a &amp; b</NewSSID><NewStandard>n</NewStandard>";
+    let response = envelope("urn:dslforum-org:service:WLANConfiguration:1", "GetInfoResponse").replace("{}", fields);
+    let mut transport = CannedTransport::new(&response);
+
+    let info = call(&mut transport, &GetInfoRequest).unwrap();
+
+    assert!(info.new_enable);
+    assert_eq!(info.new_channel, 6);
+    assert_eq!(info.new_ssid, "a & b");
+    let (control_url, soap_action, body) = &transport.requests[0];
+    assert_eq!(control_url, "/upnp/control/wlanconfig1");
+    assert_eq!(soap_action, "urn:dslforum-org:service:WLANConfiguration:1#GetInfo");
+    assert!(body.contains("<u:GetInfo xmlns:u=\"urn:dslforum-org:service:WLANConfiguration:1\">"));
+}
+
+#[test]
+fn empty_response_is_typed() {
+    let response = envelope("urn:dslforum-org:service:DeviceConfig:1", "RebootResponse").replace("{}", "");
+    let mut transport = CannedTransport::new(&response);
+
+    call(&mut transport, &RebootRequest).unwrap();
+
+    let request = SetSSIDRequest { new_ssid: String::from("FRITZ!Box") };
+    call(&mut transport, &request).unwrap();
+    assert!(transport.requests[1].2.contains("<NewSSID>FRITZ!Box</NewSSID>"));
+}
+
+#[test]
+fn invalid_response_fails() {
+    let mut transport = CannedTransport::new("<html>Not Found</html>");
+
+    let error = call(&mut transport, &GetInfoRequest).unwrap_err();
+
+    assert!(matches!(error, CallError::Deserialize(_)));
+}
+
+#[test]
+fn fault_is_typed() {
+    let mut transport = CannedTransport::new(&fault(714, "NoSuchEntryInArray"));
+
+    let error = call(&mut transport, &GetInfoRequest).unwrap_err();
+
+    match error {
+        CallError::Fault(fault) => {
+            assert_eq!(fault.fault_code, "s:Client");
+            assert_eq!(fault.error_code(), Some(ErrorCode::NoSuchEntryInArray));
+            assert_eq!(fault.to_string(), "UPnPError 714 (NoSuchEntryInArray): NoSuchEntryInArray");
+        }
+        error => panic!("unexpected error {:?}", error),
+    }
+}
+
+#[test]
+fn error_codes_round_trip() {
+    for code in [401, 402, 501, 600, 601, 602, 603, 604, 605, 606, 713, 714, 820, 866, 867, 868, 503] {
+        assert_eq!(ErrorCode::from(code).code(), code);
+    }
+    assert_eq!(ErrorCode::from(402), ErrorCode::InvalidArgs);
+    assert_eq!(ErrorCode::from(820), ErrorCode::InternalError);
+    assert_eq!(ErrorCode::from(503), ErrorCode::Other(503));
+}
+
+#[test]
+fn result_envelope_distinguishes_faults() {
+    let fields = "<NewEnable>0</NewEnable><NewStatus>Disabled</NewStatus><NewChannel>1</NewChannel>\
+                  <NewSSID></NewSSID><NewStandard>n</NewStandard>";
+    let response = envelope("urn:dslforum-org:service:WLANConfiguration:1", "GetInfoResponse").replace("{}", fields);
+
+    let ok: ResultEnvelope<GetInfoResponse> = serde_xml_rs::from_str(&response).unwrap();
+    let failed: ResultEnvelope<GetInfoResponse> = serde_xml_rs::from_str(&fault(401, "Invalid Action")).unwrap();
+
+    assert_eq!(ok.into_result().unwrap().unwrap().new_channel, 1);
+    let fault = failed.into_result().unwrap().unwrap_err();
+    assert_eq!(fault.error_code(), Some(ErrorCode::InvalidAction));
+    assert_eq!(fault.upnp_error().unwrap().error_description, "Invalid Action");
+}
@@ -0,0 +1,162 @@
+//! Parses the request bodies built by the generated files, every argument has to come back unchanged.
+
+use xml::reader::{EventReader, XmlEvent};
+
+#[allow(dead_code)]
+mod api {
+    include!("fixtures/generated/fritz_box_api.rs");
+}
+
+use api::tr064_requests::tr064_device_config::{generate_reboot_request, generate_x_avm_de_set_config_file_request};
+use api::tr064_requests::tr064_wlan_configuration::generate_set_ssid_request;
+
+const CORPUS: [&str; 16] = [
+    "",
+    "FRITZ!Box 7590",
+    "<tag>",
+    "a & b",
+    "&amp; already escaped",
+    "\"double\" and 'single' quotes",
+    "]]>",
+    "<![CDATA[x]]>",
+    "</NewSSID><NewInjected>x</NewInjected><NewSSID>",
+    "<?xml version=\"1.0\"?>",
+    "<!-- comment -->",
+    "tab\tnew line\nwindows\r\nold mac\r",
+    "  leading and trailing  ",
+    " ",
+    "ünïcödé 中文 😀",
+    "\u{7f}\u{fffd}\u{10ffff}",
+];
+
+/// Values with characters XML 1.0 can't represent and the values sent instead.
+const UNREPRESENTABLE: [(&str, &str); 5] = [
+    ("\u{0}", "\u{fffd}"),
+    ("bell\u{7}", "bell\u{fffd}"),
+    ("\u{1}\u{8}\u{b}\u{c}\u{e}\u{1b}\u{1f}", "\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}"),
+    ("tab\tvertical tab\u{b}", "tab\tvertical tab\u{fffd}"),
+    ("\u{fffe}\u{ffff}", "\u{fffd}\u{fffd}"),
+];
+
+/// The action element of a request body and its arguments.
+#[derive(Debug, PartialEq)]
+struct Call {
+    action: String,
+    service_type: String,
+    arguments: Vec<(String, String)>,
+}
+
+/// Parses `body`, panicking if it isn't well-formed.
+fn parse(body: &str) -> Call {
+    let mut call = Call {
+        action: String::new(),
+        service_type: String::new(),
+        arguments: vec![],
+    };
+    let mut depth = 0;
+
+    for event in EventReader::new(body.as_bytes()) {
+        match event.unwrap_or_else(|e| panic!("{} in\n{}", e, body)) {
+            XmlEvent::StartElement { name, namespace, .. } => {
+                depth += 1;
+                match depth {
+                    1 => assert_eq!(name.local_name, "Envelope"),
+                    2 => assert_eq!(name.local_name, "Body"),
+                    3 => {
+                        call.action = name.local_name;
+                        call.service_type = namespace.get("u").unwrap_or_default().to_string();
+                    }
+                    4 => call.arguments.push((name.local_name, String::new())),
+                    _ => panic!("unexpected element {} in\n{}", name, body),
+                }
+            }
+            XmlEvent::EndElement { .. } => depth -= 1,
+            XmlEvent::Characters(text) | XmlEvent::Whitespace(text) | XmlEvent::CData(text) if depth == 4 => {
+                call.arguments.last_mut().unwrap().1.push_str(&text);
+            }
+            _ => {}
+        }
+    }
+
+    call
+}
+
+/// Returns `count` strings of up to 20 XML 1.0 characters, favoring the ones that need escaping.
+fn random_strings(count: usize) -> Vec<String> {
+    const SPECIAL: [char; 10] = ['<', '>', '&', '"', '\'', ';', '#', '\t', '\n', '\r'];
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move |bound: u64| {
+        // xorshift, deterministic to keep failures reproducible
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state % bound
+    };
+
+    (0..count)
+        .map(|_| {
+            (0..next(21))
+                .map(|_| match next(4) {
+                    0 => SPECIAL[next(SPECIAL.len() as u64) as usize],
+                    1 => char::from(0x20 + next(0x5f) as u8),
+                    2 => char::from_u32(0xa0 + next(0xd7ff - 0xa0) as u32).unwrap(),
+                    _ => char::from_u32(0x10000 + next(0x100000) as u32).unwrap(),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn envelope_is_well_formed() {
+    let (uri, header, body) = generate_reboot_request();
+
+    assert_eq!(uri, "/upnp/control/deviceconfig");
+    assert_eq!(header, "urn:dslforum-org:service:DeviceConfig:1#Reboot");
+    assert!(body.starts_with("<?xml version=\"1.0\"?>\n"));
+    assert!(body.contains(
+        "xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\""
+    ));
+    assert_eq!(
+        parse(&body),
+        Call {
+            action: "Reboot".to_string(),
+            service_type: "urn:dslforum-org:service:DeviceConfig:1".to_string(),
+            arguments: vec![],
+        }
+    );
+}
+
+#[test]
+fn corpus_round_trips() {
+    for value in CORPUS {
+        let (_, _, body) = generate_set_ssid_request(value);
+
+        assert_eq!(parse(&body).arguments, vec![("NewSSID".to_string(), value.to_string())], "{:?}", value);
+    }
+}
+
+#[test]
+fn unrepresentable_characters_are_replaced() {
+    for (value, sent) in UNREPRESENTABLE {
+        let (_, _, body) = generate_set_ssid_request(value);
+
+        assert_eq!(parse(&body).arguments, vec![("NewSSID".to_string(), sent.to_string())], "{:?}", value);
+    }
+}
+
+#[test]
+fn random_strings_round_trip() {
+    let values = random_strings(500);
+    for pair in values.chunks(2) {
+        let (_, _, body) = generate_x_avm_de_set_config_file_request(&pair[0], &pair[1]);
+
+        assert_eq!(
+            parse(&body).arguments,
+            vec![
+                ("NewX_AVM-DE_Password".to_string(), pair[0].clone()),
+                ("NewX_AVM-DE_ConfigFileUrl".to_string(), pair[1].clone()),
+            ]
+        );
+    }
+}
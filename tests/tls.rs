@@ -0,0 +1,250 @@
+//! Fetches descriptions and calls actions over HTTPS from a local stand-in with a freshly generated certificate.
+
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::process::Command;
+use std::sync::Arc;
+use std::thread;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use rustls::{ServerConfig, ServerConnection, StreamOwned};
+use fritz_box_tr064_igd_api_files_generator::api_handling::inspect::summarize;
+use fritz_box_tr064_igd_api_files_generator::api_handling::source::DescriptionSource;
+use fritz_box_tr064_igd_api_files_generator::config::SourceConfig;
+use fritz_box_tr064_igd_api_files_generator::tls::Fingerprint;
+use fritz_box_tr064_igd_api_files_generator::GeneratorError;
+
+#[allow(dead_code)]
+mod api {
+    include!("fixtures/generated/fritz_box_api.rs");
+}
+
+use api::client::{self, AsyncFritzClient, FritzClient};
+use api::soap::CallError;
+
+const SNAPSHOT: &str = "tests/fixtures/snapshot";
+
+const GET_INFO_RESPONSE: &str = "<?xml version=\"1.0\"?>\n\
+    <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\n\
+    <s:Body>\n<u:GetInfoResponse xmlns:u=\"urn:dslforum-org:service:WLANConfiguration:1\">\
+    <NewEnable>1</NewEnable><NewStatus>Up</NewStatus><NewChannel>36</NewChannel>\
+    <NewSSID>FRITZ!Box 7590</NewSSID><NewStandard>ac</NewStandard></u:GetInfoResponse>\n</s:Body>\n</s:Envelope>";
+
+/// A self-signed certificate for `localhost`, like the one a FRITZ!Box creates for itself.
+struct Certificate {
+    der: Vec<u8>,
+    pem: String,
+    key: Vec<u8>,
+}
+
+impl Certificate {
+    fn generate() -> Self {
+        let certificate = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        // serialized once, every serialization signs anew
+        let pem = certificate.serialize_pem().unwrap();
+        let der = rustls_pemfile::certs(&mut pem.as_bytes()).next().unwrap().unwrap().to_vec();
+        Certificate {
+            der,
+            pem,
+            key: certificate.serialize_private_key_der(),
+        }
+    }
+}
+
+/// Serves the snapshot files to `GET` and answers every `POST` with a `GetInfo` response over HTTPS, returns the base
+/// URL. Failed handshakes are expected, they close the connection.
+fn serve(certificate: &Certificate) -> String {
+    let config = ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_no_client_auth()
+        .with_single_cert(
+            vec![CertificateDer::from(certificate.der.clone())],
+            PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(certificate.key.clone())),
+        )
+        .unwrap();
+    let config = Arc::new(config);
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("https://localhost:{}", listener.local_addr().unwrap().port());
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let connection = ServerConnection::new(config.clone()).unwrap();
+            let mut reader = BufReader::new(StreamOwned::new(connection, stream.unwrap()));
+            let mut line = String::new();
+            if reader.read_line(&mut line).is_err() {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let method = parts.next().unwrap_or_default().to_string();
+            let path = parts.next().unwrap_or_default().to_string();
+
+            let mut length = 0;
+            loop {
+                line.clear();
+                reader.read_line(&mut line).unwrap();
+                match line.trim_end().split_once(':') {
+                    Some((name, value)) if name.eq_ignore_ascii_case("content-length") => {
+                        length = value.trim().parse().unwrap();
+                    }
+                    Some(_) => {}
+                    None => break,
+                }
+            }
+            reader.read_exact(&mut vec![0; length]).unwrap();
+
+            let (status, body) = match method.as_str() {
+                "GET" => match fs::read_to_string(format!("{}{}", SNAPSHOT, path)) {
+                    Ok(description) => (200, description),
+                    Err(_) => (404, String::new()),
+                },
+                _ => (200, GET_INFO_RESPONSE.to_string()),
+            };
+            let mut stream = reader.into_inner();
+            write!(
+                stream,
+                "HTTP/1.1 {} Stand-in\r\nContent-Type: text/xml; charset=\"utf-8\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+            stream.conn.send_close_notify();
+            let _ = stream.flush();
+        }
+    });
+
+    base_url
+}
+
+fn source(address: String, fingerprints: Vec<Fingerprint>) -> SourceConfig {
+    SourceConfig {
+        address,
+        fingerprints,
+        ..SourceConfig::default()
+    }
+}
+
+#[test]
+fn descriptions_are_fetched_with_a_pinned_fingerprint() {
+    let certificate = Certificate::generate();
+    let address = serve(&certificate);
+    let fingerprint: Fingerprint = Fingerprint::of(&certificate.der).to_string().parse().unwrap();
+
+    let source = source(address, vec![fingerprint]).source().unwrap();
+    let summary = summarize(&source, "/tr64desc.xml").unwrap();
+
+    assert!(matches!(source, DescriptionSource::Https { .. }));
+    let expected = summarize(&DescriptionSource::Snapshot(SNAPSHOT.into()), "/tr64desc.xml").unwrap();
+    assert_eq!(summary.model_name, expected.model_name);
+    assert_eq!(summary.services, expected.services);
+}
+
+#[test]
+fn descriptions_are_fetched_with_a_pinned_pem() {
+    let certificate = Certificate::generate();
+    let address = serve(&certificate);
+    let path = env::temp_dir().join(format!("fritzgen-tls-{}.pem", std::process::id()));
+    fs::write(&path, &certificate.pem).unwrap();
+
+    let mut config = source(address, vec![]);
+    config.certificate = Some(path.clone());
+    let summary = summarize(&config.source().unwrap(), "/igddesc.xml");
+    fs::remove_file(&path).unwrap();
+
+    assert!(!summary.unwrap().services.is_empty());
+}
+
+#[test]
+fn other_certificate_is_refused() {
+    let address = serve(&Certificate::generate());
+    let other = Certificate::generate();
+
+    let source = source(address, vec![Fingerprint::of(&other.der)]).source().unwrap();
+    let error = summarize(&source, "/tr64desc.xml").unwrap_err();
+
+    assert!(matches!(error, GeneratorError::Fetch { .. }), "unexpected error {:?}", error);
+}
+
+#[test]
+fn client_calls_with_a_pinned_certificate() {
+    let certificate = Certificate::generate();
+    let base_url = serve(&certificate);
+    let fingerprints = client::Fingerprint::of_pem(certificate.pem.as_bytes()).unwrap();
+    let other: client::Fingerprint = Fingerprint::of(&Certificate::generate().der).to_string().parse().unwrap();
+
+    let info = FritzClient::new(&base_url)
+        .with_pinned_certificates(&fingerprints)
+        .unwrap()
+        .tr064_wlan_configuration()
+        .get_info()
+        .unwrap();
+    let error = FritzClient::new(&base_url)
+        .with_pinned_certificates(&[other])
+        .unwrap()
+        .tr064_wlan_configuration()
+        .get_info()
+        .unwrap_err();
+    // without pinning the self-signed certificate isn't trusted
+    let unpinned = FritzClient::new(&base_url).tr064_wlan_configuration().get_info().unwrap_err();
+
+    assert_eq!(info.new_channel, 36);
+    assert!(matches!(error, CallError::Transport(_)), "unexpected error {:?}", error);
+    assert!(matches!(unpinned, CallError::Transport(_)), "unexpected error {:?}", unpinned);
+}
+
+#[tokio::test]
+async fn async_client_calls_with_a_pinned_certificate() {
+    let certificate = Certificate::generate();
+    let base_url = serve(&certificate);
+    let fingerprint: client::Fingerprint = Fingerprint::of(&certificate.der).to_string().parse().unwrap();
+
+    let info = AsyncFritzClient::new(&base_url)
+        .with_pinned_certificates(&[fingerprint])
+        .unwrap()
+        .tr064_wlan_configuration()
+        .get_info()
+        .await
+        .unwrap();
+
+    assert_eq!(info.new_ssid, "FRITZ!Box 7590");
+}
+
+#[test]
+fn diff_pins_the_certificate_of_https_addresses() {
+    let certificate = Certificate::generate();
+    let address = serve(&certificate);
+    let diff = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_fritz_box_tr064_igd_api_files_generator"))
+            .args(["diff", &address, SNAPSHOT, "--descriptions", "tr064"])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    let pinned = diff(&["--fingerprint", &Fingerprint::of(&certificate.der).to_string()]);
+    let unpinned = diff(&[]);
+
+    assert_eq!(pinned.status.code(), Some(0), "{}", String::from_utf8_lossy(&pinned.stderr));
+    // without pinning the self-signed certificate isn't trusted
+    assert_eq!(unpinned.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&unpinned.stderr).contains("error:"));
+}
+
+#[test]
+fn pinning_needs_an_https_address() {
+    let certificate = Certificate::generate();
+
+    let error = source("http://fritz.box:49000".to_string(), vec![Fingerprint::of(&certificate.der)])
+        .source()
+        .unwrap_err();
+
+    assert!(matches!(error, GeneratorError::Config { .. }), "unexpected error {:?}", error);
+}
+
+#[test]
+fn pem_without_certificate_is_refused() {
+    assert!(Fingerprint::of_pem(b"no certificate").is_err());
+    assert!(client::Fingerprint::of_pem(b"no certificate").is_err());
+}
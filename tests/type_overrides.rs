@@ -0,0 +1,150 @@
+//! Checks which rust type the generator picks for an argument: argument overrides take precedence over state variable
+//! overrides, which take precedence over `type_mapping`.
+
+use fritz_box_tr064_igd_api_files_generator::{Generator, GeneratorError, MemorySink};
+use fritz_box_tr064_igd_api_files_generator::api_handling::source::DescriptionSource;
+use fritz_box_tr064_igd_api_files_generator::config::{Description, TypeOverride};
+
+const SNAPSHOT: &str = "tests/fixtures/snapshot";
+
+/// Generated request and response file of `WLANConfiguration`.
+struct WlanFiles {
+    requests: String,
+    responses: String,
+}
+
+fn generate(generator: Generator<'_>) -> WlanFiles {
+    let mut sink = MemorySink::default();
+    generator
+        .source(DescriptionSource::Snapshot(SNAPSHOT.into()))
+        .descriptions(&[Description::Tr064])
+        .include_service("WLANConfiguration")
+        .sink(&mut sink)
+        .generate()
+        .unwrap();
+    let file = |folder: &str| {
+        sink.files
+            .iter()
+            .find(|(path, _)| path.ends_with(format!("{}/tr064_wlan_configuration.rs", folder)))
+            .map(|(_, content)| content.clone())
+            .unwrap_or_else(|| panic!("no {} file in {:?}", folder, sink.files.keys()))
+    };
+
+    WlanFiles {
+        requests: file("tr064_requests"),
+        responses: file("tr064_responses"),
+    }
+}
+
+/// Overrides the `Channel` state variable of `service`.
+fn variable_override(service: &str, type_name: &str) -> TypeOverride {
+    TypeOverride {
+        service: service.to_string(),
+        variable: Some("Channel".to_string()),
+        action: None,
+        argument: None,
+        type_name: type_name.to_string(),
+        deserialize_with: None,
+        format_with: None,
+    }
+}
+
+/// Overrides the argument `NewChannel` of `SetChannel`.
+fn argument_override(type_name: &str) -> TypeOverride {
+    TypeOverride {
+        action: Some("SetChannel".to_string()),
+        argument: Some("NewChannel".to_string()),
+        variable: None,
+        ..variable_override("urn:dslforum-org:service:WLANConfiguration:1", type_name)
+    }
+}
+
+#[test]
+fn type_mapping_replaces_the_built_in_type() {
+    let files = generate(Generator::new().type_mapping("ui1", "u16"));
+
+    assert!(files.responses.contains("pub new_channel: u16,"), "{}", files.responses);
+    assert!(files.requests.contains("pub new_channel: u16,"), "{}", files.requests);
+}
+
+#[test]
+fn variable_override_takes_precedence_over_type_mapping() {
+    let files = generate(
+        Generator::new()
+            .type_mapping("ui1", "u16")
+            .type_override(variable_override("WLANConfiguration", "crate::Channel")),
+    );
+
+    assert!(files.responses.contains("pub new_channel: crate::Channel,"), "{}", files.responses);
+    assert!(files.requests.contains("pub new_channel: crate::Channel,"), "{}", files.requests);
+    assert!(!files.responses.contains("u16") && !files.requests.contains("u16"));
+}
+
+#[test]
+fn argument_override_takes_precedence_over_variable_override() {
+    // the order of the overrides doesn't matter
+    for overrides in [
+        [argument_override("crate::SetChannel"), variable_override("WLANConfiguration", "crate::Channel")],
+        [variable_override("WLANConfiguration", "crate::Channel"), argument_override("crate::SetChannel")],
+    ] {
+        let generator = overrides
+            .iter()
+            .cloned()
+            .fold(Generator::new().type_mapping("ui1", "u16"), Generator::type_override);
+        let files = generate(generator);
+
+        // the argument of `GetInfo` is related to the same state variable
+        assert!(files.responses.contains("pub new_channel: crate::Channel,"), "{}", files.responses);
+        assert!(files.requests.contains("pub new_channel: crate::SetChannel,"), "{}", files.requests);
+    }
+}
+
+#[test]
+fn override_of_other_service_is_ignored() {
+    let files = generate(Generator::new().type_override(variable_override("DeviceConfig", "crate::Channel")));
+
+    assert!(files.responses.contains("pub new_channel: u32,"), "{}", files.responses);
+    assert!(!files.requests.contains("crate::Channel"));
+}
+
+#[test]
+fn override_glue_is_emitted() {
+    let files = generate(Generator::new().type_override(TypeOverride {
+        deserialize_with: Some("crate::channel::deserialize".to_string()),
+        format_with: Some("crate::channel::format".to_string()),
+        ..variable_override("urn:dslforum-org:service:WLANConfiguration:1", "crate::Channel")
+    }));
+
+    assert!(
+        files.responses.contains(
+            "    #[serde(deserialize_with = \"crate::channel::deserialize\")]\n    pub new_channel: crate::Channel,"
+        ),
+        "{}",
+        files.responses
+    );
+    assert!(
+        files.requests.contains("(\"NewChannel\", crate::channel::format(new_channel).to_string()),"),
+        "{}",
+        files.requests
+    );
+    // without `format_with` the type is formatted with `Display`
+    assert!(files.requests.contains("(\"NewSSID\", new_ssid.to_string()),"), "{}", files.requests);
+}
+
+#[test]
+fn override_without_selector_fails() {
+    let mut sink = MemorySink::default();
+
+    let result = Generator::new()
+        .source(DescriptionSource::Snapshot(SNAPSHOT.into()))
+        .descriptions(&[Description::Tr064])
+        .type_override(TypeOverride {
+            variable: None,
+            ..variable_override("WLANConfiguration", "crate::Channel")
+        })
+        .sink(&mut sink)
+        .generate();
+
+    assert!(matches!(result, Err(GeneratorError::Config { .. })), "unexpected result {:?}", result.err());
+    assert!(sink.files.is_empty());
+}
@@ -0,0 +1,80 @@
+//! Deserializes and formats the chrono, uuid and bytes types of `tests/fixtures/options/x_typesSCPD.xml`.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+
+#[allow(dead_code)]
+mod api {
+    include!("fixtures/generated/typed_values_api.rs");
+}
+
+use api::soap::{response, SoapAction};
+use api::tr064_requests::tr064_x_types::{GetValuesRequest, SetValuesRequest};
+
+fn values_response(date_time: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\"?>\n\
+         <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\n\
+         <s:Body>\n<u:GetValuesResponse xmlns:u=\"urn:dslforum-org:service:X_Types:1\">\
+         <NewDate>2024-02-29</NewDate><NewDateTime>{}</NewDateTime><NewDateTimeTz>2024-02-29T13:37:00+01:00</NewDateTimeTz>\
+         <NewUUID>739f2409-bccb-40e7-8e6c-3431c4a1b2c3</NewUUID><NewBase64>RlJJVFohQm94</NewBase64><NewHex>00ff7f</NewHex>\
+         </u:GetValuesResponse>\n</s:Body>\n</s:Envelope>",
+        date_time
+    )
+}
+
+fn date_time(value: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f").unwrap()
+}
+
+#[test]
+fn values_are_deserialized() {
+    let values = response::<GetValuesRequest>(&values_response("2024-02-29T13:37:00")).unwrap();
+
+    assert_eq!(values.new_date, NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+    assert_eq!(values.new_date_time, date_time("2024-02-29T13:37:00"));
+    assert_eq!(values.new_date_time_tz, DateTime::parse_from_rfc3339("2024-02-29T12:37:00Z").unwrap());
+    assert_eq!(values.new_uuid.to_string(), "739f2409-bccb-40e7-8e6c-3431c4a1b2c3");
+    assert_eq!(values.new_base64, b"FRITZ!Box");
+    assert_eq!(values.new_hex, [0x00, 0xff, 0x7f]);
+}
+
+#[test]
+fn date_time_drops_time_zone_and_keeps_fraction() {
+    let with_zone = response::<GetValuesRequest>(&values_response("2024-02-29T13:37:00+01:00")).unwrap();
+    let with_fraction = response::<GetValuesRequest>(&values_response("2024-02-29T13:37:00.250")).unwrap();
+
+    assert_eq!(with_zone.new_date_time, date_time("2024-02-29T13:37:00"));
+    assert_eq!(with_fraction.new_date_time, date_time("2024-02-29T13:37:00.250"));
+    assert!(response::<GetValuesRequest>(&values_response("yesterday")).is_err());
+}
+
+#[test]
+fn invalid_binaries_fail() {
+    let invalid_base64 = values_response("2024-02-29T13:37:00").replace("RlJJVFohQm94", "not base64!");
+    let invalid_hex = values_response("2024-02-29T13:37:00").replace("00ff7f", "0g");
+
+    assert!(response::<GetValuesRequest>(&invalid_base64).is_err());
+    assert!(response::<GetValuesRequest>(&invalid_hex).is_err());
+}
+
+#[test]
+fn values_are_formatted() {
+    let request = SetValuesRequest {
+        new_date: NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+        new_date_time: date_time("2024-02-29T13:37:00.250"),
+        new_date_time_tz: DateTime::parse_from_rfc3339("2024-02-29T13:37:00+01:00").unwrap(),
+        new_uuid: "739F2409-BCCB-40E7-8E6C-3431C4A1B2C3".parse().unwrap(),
+        new_base64: b"FRITZ!Box".to_vec(),
+        new_hex: vec![0x00, 0xff, 0x7f],
+    };
+
+    let body = request.body();
+
+    assert!(body.contains("<NewDate>2024-02-29</NewDate>"), "{}", body);
+    // the fraction isn't part of the UPnP dateTime
+    assert!(body.contains("<NewDateTime>2024-02-29T13:37:00</NewDateTime>"), "{}", body);
+    assert!(body.contains("<NewDateTimeTz>2024-02-29T13:37:00+01:00</NewDateTimeTz>"), "{}", body);
+    assert!(body.contains("<NewUUID>739f2409-bccb-40e7-8e6c-3431c4a1b2c3</NewUUID>"), "{}", body);
+    assert!(body.contains("<NewBase64>RlJJVFohQm94</NewBase64>"), "{}", body);
+    assert!(body.contains("<NewHex>00ff7f</NewHex>"), "{}", body);
+}